@@ -0,0 +1,143 @@
+//! Known answers for each day's real input, stored in `src/bin/<day>/answers.toml`
+//! as one `part = "answer"` line per operation.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail { expected: String },
+    New,
+    Recorded,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pass => "PASS",
+            Self::Fail { .. } => "FAIL",
+            Self::New => "NEW",
+            Self::Recorded => "RECORDED",
+        })
+    }
+}
+
+pub struct Answers {
+    path: PathBuf,
+    answers: BTreeMap<String, String>,
+}
+
+impl Answers {
+    pub fn path(day_name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/bin")
+            .join(day_name)
+            .join("answers.toml")
+    }
+
+    /// Loads the answers for a day, which are empty if none were recorded yet.
+    pub fn load(day_name: &str) -> io::Result<Self> {
+        let path = Self::path(day_name);
+        let answers = match fs::read_to_string(&path) {
+            Ok(contents) => parse(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, answers })
+    }
+
+    pub fn check(&self, part: &str, answer: &str) -> Check {
+        match self.answers.get(part) {
+            Some(expected) if expected == answer => Check::Pass,
+            Some(expected) => Check::Fail { expected: expected.clone() },
+            None => Check::New,
+        }
+    }
+
+    pub fn record(&mut self, part: &str, answer: &str) -> Check {
+        self.answers.insert(part.to_string(), answer.to_string());
+        Check::Recorded
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(&self.path, format(&self.answers))
+    }
+}
+
+fn parse(contents: &str) -> Result<BTreeMap<String, String>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            let (key, value) = line.split_once('=').ok_or(format!("line {}: expected key = \"value\"", i + 1))?;
+            let value = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .ok_or(format!("line {}: expected quoted value", i + 1))?;
+            Ok((key.trim().to_string(), unescape(value)))
+        })
+        .collect()
+}
+
+fn format(answers: &BTreeMap<String, String>) -> String {
+    answers
+        .iter()
+        .map(|(part, answer)| format!("{} = \"{}\"\n", part, escape(answer)))
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let answers = BTreeMap::from([
+            ("part1".to_string(), "142".to_string()),
+            ("part2".to_string(), "a \"quoted\"\nmulti\\line".to_string()),
+        ]);
+        let formatted = format(&answers);
+        assert_eq!(formatted.lines().next(), Some("part1 = \"142\""));
+        assert_eq!(parse(&formatted), Ok(answers));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers {
+            path: PathBuf::new(),
+            answers: parse("# comment\n\npart1 = \"8\"\n").unwrap(),
+        };
+        assert_eq!(answers.check("part1", "8"), Check::Pass);
+        assert_eq!(answers.check("part1", "9"), Check::Fail { expected: "8".to_string() });
+        assert_eq!(answers.check("part2", "9"), Check::New);
+        assert!(parse("part1 = 8").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use advent_of_code::{Day, Runner};
+use std::process::ExitCode;
+
+#[allow(dead_code)]
+#[path = "../day01/main.rs"]
+mod day01;
+#[allow(dead_code)]
+#[path = "../day02/main.rs"]
+mod day02;
+#[allow(dead_code)]
+#[path = "../day03/main.rs"]
+mod day03;
+#[allow(dead_code)]
+#[path = "../day04/main.rs"]
+mod day04;
+#[allow(dead_code)]
+#[path = "../day05/main.rs"]
+mod day05;
+#[allow(dead_code)]
+#[path = "../day06/main.rs"]
+mod day06;
+#[allow(dead_code)]
+#[path = "../day07/main.rs"]
+mod day07;
+#[allow(dead_code)]
+#[path = "../day08/main.rs"]
+mod day08;
+#[allow(dead_code)]
+#[path = "../day09/main.rs"]
+mod day09;
+#[allow(dead_code)]
+#[path = "../day10/main.rs"]
+mod day10;
+
+/// Every day, in order. Add new days here after copying the template.
+const DAYS: &[Day] = &[
+    day01::DAY,
+    day02::DAY,
+    day03::DAY,
+    day04::DAY,
+    day05::DAY,
+    day06::DAY,
+    day07::DAY,
+    day08::DAY,
+    day09::DAY,
+    day10::DAY,
+];
+
+fn main() -> ExitCode {
+    Runner::create().run(DAYS)
+}
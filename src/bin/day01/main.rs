@@ -1,6 +1,6 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use lazy_regex::{Regex, Lazy, lazy_regex};
-use std::str::Lines;
+use advent_of_code::{day, scanner::Scanner, Day, Runner};
+use itertools::Itertools;
+use std::{process::ExitCode, str::Lines};
 
 fn part1(input: Lines) -> String {
     input
@@ -15,45 +15,97 @@ fn part1(input: Lines) -> String {
         .to_string()
 }
 
-fn parse_num(num: &str) -> Option<u32> {
-    match num {
-        "1" | "one" | "eno" => Some(1),
-        "2" | "two" | "owt" => Some(2),
-        "3" | "three" | "eerht" => Some(3),
-        "4" | "four" | "ruof" => Some(4),
-        "5" | "five" | "evif" => Some(5),
-        "6" | "six" | "xis" => Some(6),
-        "7" | "seven" | "neves" => Some(7),
-        "8" | "eight" | "thgie" => Some(8),
-        "9" | "nine" | "enin" => Some(9),
-        _ => None
-    }
+/// Words for the digits zero to nine in one language.
+struct Vocabulary {
+    name: &'static str,
+    words: [&'static str; 10],
 }
 
-pub static DIGIT_REGEX: Lazy<Regex> = lazy_regex!("[1-9]|one|two|three|four|five|six|seven|eight|nine");
-pub static DIGIT_REGEX_REV: Lazy<Regex> = lazy_regex!("[1-9]|eno|owt|eerht|ruof|evif|xis|neves|thgie|enin");
+const VOCABULARIES: &[Vocabulary] = &[
+    Vocabulary {
+        name: "english",
+        words: ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"],
+    },
+    Vocabulary {
+        name: "german",
+        words: ["null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun"],
+    },
+    Vocabulary {
+        name: "french",
+        words: ["zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf"],
+    },
+    Vocabulary {
+        name: "spanish",
+        words: ["cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve"],
+    },
+];
 
-fn first_last_digit_words(line: &str) -> Option<(u32, u32)> {
-    let first = DIGIT_REGEX.find(line).and_then(|m| parse_num(m.as_str()));
-    let reversed: String = line.chars().rev().collect();
-    let last = DIGIT_REGEX_REV.find(&reversed).and_then(|m| parse_num(m.as_str()));
-    first.zip(last)
+fn vocabulary(name: &str) -> Result<&'static Vocabulary, String> {
+    VOCABULARIES.iter().find(|v| v.name == name).ok_or_else(|| {
+        format!("unknown vocabulary {:?}, expected one of {}", name, VOCABULARIES.iter().map(|v| v.name).join(", "))
+    })
 }
 
-fn part2(input: Lines) -> String {
-    input
-        .map(|line| {
-            first_last_digit_words(line).map(|(a, b)| a * 10 + b).unwrap_or_default()
-        })
-        .sum::<u32>()
-        .to_string()
+/// Finds digits written as digits or as words from some vocabularies, where
+/// words may overlap like `twone`.
+struct Calibration {
+    scanner: Scanner<u32>,
+}
+
+impl Calibration {
+    /// Digits one to nine, and zero only if `zero` is set since the puzzle
+    /// doesn't count it.
+    fn new(vocabularies: &[&Vocabulary], zero: bool) -> Self {
+        let first = if zero { 0 } else { 1 };
+        let digits = (first..10).map(|d| (d.to_string(), d));
+        let words = vocabularies
+            .iter()
+            .flat_map(|v| (first..10).map(|d| (v.words[d as usize].to_string(), d)));
+        Self { scanner: Scanner::new(digits.chain(words)) }
+    }
+
+    /// The vocabularies from `--vocabulary=english,german` (default english)
+    /// and zero if `--zero` is given.
+    fn from_options() -> Result<Self, String> {
+        let vocabularies = advent_of_code::option("vocabulary")
+            .unwrap_or("english")
+            .split(',')
+            .map(vocabulary)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(&vocabularies, advent_of_code::option("zero").is_some()))
+    }
+
+    fn first_last_digits(&self, line: &str) -> Option<(u32, u32)> {
+        let mut first: Option<(usize, u32)> = None;
+        let mut last = None;
+        for m in self.scanner.find_overlapping(line) {
+            // matches come in order of where they end, which for a token inside
+            // another isn't the order they start
+            if first.is_none_or(|(start, _)| m.start < start) {
+                first = Some((m.start, *m.value));
+            }
+            last = Some(*m.value);
+        }
+        first.map(|(_, digit)| digit).zip(last)
+    }
+
+    fn sum(&self, input: Lines) -> u32 {
+        input
+            .map(|line| {
+                self.first_last_digits(line).map(|(a, b)| a * 10 + b).unwrap_or_default()
+            })
+            .sum::<u32>()
+    }
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+fn part2(input: Lines) -> Result<u32, String> {
+    Ok(Calibration::from_options()?.sum(input))
+}
+
+pub const DAY: Day = day!(part1, part2; options = ["vocabulary", "zero"]);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -63,6 +115,8 @@ mod tests {
 
     #[test]
     fn test_first_last_digit_words() {
+        let calibration = Calibration::from_options().unwrap();
+        let first_last_digit_words = |line| calibration.first_last_digits(line);
         assert_eq!(first_last_digit_words("abc"), None);
         assert_eq!(first_last_digit_words("one"), Some((1, 1)));
         assert_eq!(first_last_digit_words("oneight"), Some((1, 8)));
@@ -82,4 +136,25 @@ mod tests {
         let input2 = include_str!("example2.txt");
         verify!(part2, input2, "281");
     }
+
+    #[test]
+    fn vocabularies() {
+        let english = vocabulary("english").unwrap();
+        let input2 = include_str!("example2.txt");
+        assert_eq!(Calibration::new(&[english], false).sum(input2.lines()), 281);
+        assert_eq!(Calibration::new(&[english], true).sum(input2.lines()), 281);
+        assert_eq!(Calibration::new(&[], false).sum(input2.lines()), 209);
+
+        let zero = Calibration::new(&[english], true);
+        assert_eq!(zero.first_last_digits("zero5nine"), Some((0, 9)));
+        assert_eq!(Calibration::new(&[english], false).first_last_digits("zero5nine"), Some((5, 9)));
+        assert_eq!(zero.first_last_digits("0"), Some((0, 0)));
+
+        let german = vocabulary("german").unwrap();
+        let french = vocabulary("french").unwrap();
+        let both = Calibration::new(&[german, french], false);
+        assert_eq!(both.first_last_digits("xfünfeightsept"), Some((5, 7)));
+        assert_eq!(both.first_last_digits("huitneins"), Some((8, 1)));
+        assert!(vocabulary("klingon").is_err());
+    }
 }
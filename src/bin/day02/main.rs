@@ -1,76 +1,133 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
+use advent_of_code::{day, Day, ParseError, ParseLines, Runner};
 use lazy_regex::regex_captures;
-use std::{str::{Lines, FromStr}, cmp::max};
+use std::{str::Lines, cmp::max, collections::BTreeMap, error::Error, process::ExitCode};
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseError;
+/// Index of a cube color within a [Palette].
+type ColorId = usize;
 
-#[derive(Debug, PartialEq, Eq)]
-enum Color {
-    Red,
-    Green,
-    Blue,
+/// The cube colors which may be drawn, interned so samples can be keyed by id.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Palette {
+    names: Vec<String>,
+    /// Whether colors not seen yet are added rather than rejected.
+    open: bool,
 }
 
-impl FromStr for Color {
-    type Err = ParseError;
+impl Palette {
+    /// A palette which learns its colors from the games as they're parsed.
+    fn open() -> Self {
+        Self { names: Vec::new(), open: true }
+    }
+
+    fn id(&self, name: &str) -> Option<ColorId> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn intern(&mut self, name: &str) -> ColorId {
+        self.id(name).unwrap_or_else(|| {
+            self.names.push(name.to_string());
+            self.names.len() - 1
+        })
+    }
+
+    fn resolve(&mut self, name: &str) -> Option<ColorId> {
+        if self.open {
+            Some(self.intern(name))
+        } else {
+            self.id(name)
+        }
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "red" => Ok(Color::Red),
-            "green" => Ok(Color::Green),
-            "blue" => Ok(Color::Blue),
-            _ => Err(ParseError)
+    /// The colors for an error message, like `red, green or blue`.
+    fn describe(&self) -> String {
+        match self.names.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => String::from("no colors"),
         }
     }
 }
 
-#[derive(Default, Debug, PartialEq, Eq)]
+/// How many cubes of each color, as a multiset where colors which aren't
+/// present count as zero.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 struct Sample {
-    colors: [u32; 3],
+    counts: BTreeMap<ColorId, u32>,
 }
 
 impl Sample {
-    fn add(&self, count: u32, color: Color) -> Self {
-        let mut colors = self.colors;
-        colors[color as usize] += count;
-        Self { colors }
+    fn add(mut self, count: u32, color: ColorId) -> Self {
+        *self.counts.entry(color).or_default() += count;
+        self
+    }
+
+    fn count(&self, color: ColorId) -> u32 {
+        self.counts.get(&color).copied().unwrap_or_default()
     }
 
     fn is_possible(&self, cubes: &Sample) -> bool {
-        self.colors.iter().zip(cubes.colors.iter()).all(|(s, c)| s <= c)
+        self.counts.iter().all(|(&color, &count)| count <= cubes.count(color))
     }
 
     fn max_by_color(&self, other: &Sample) -> Sample {
-        let colors = [
-            max(self.colors[0], other.colors[0]),
-            max(self.colors[1], other.colors[1]),
-            max(self.colors[2], other.colors[2]),
-        ];
-        Sample{colors}
+        other.counts.iter().fold(self.clone(), |mut result, (&color, &count)| {
+            let max_count = result.counts.entry(color).or_default();
+            *max_count = max(*max_count, count);
+            result
+        })
     }
 
-    fn power(&self) -> u32 {
-        self.colors.iter().product()
+    /// Product of the counts of every color in the palette.
+    fn power(&self, palette: &Palette) -> u32 {
+        (0..palette.names.len()).map(|color| self.count(color)).product()
     }
-}
 
-impl FromStr for Sample {
-    type Err = ParseError;
-
-    fn from_str(sample: &str) -> Result<Self, Self::Err> {
+    fn parse(sample: &str, palette: &mut Palette) -> Result<Self, ParseError> {
         sample
             .split(',')
             .try_fold(Sample::default(), |result, s| {
-                let (_, count, color) = regex_captures!(r"([0-9]+)\s+(red|green|blue)", s).ok_or(ParseError)?;
-                let count = count.parse::<u32>().map_err(|_| ParseError)?;
-                let color = color.parse::<Color>()?;
+                let (_, count, color) = regex_captures!(r"^\s*([0-9]+)\s+(\S+)\s*$", s)
+                    .ok_or_else(|| ParseError::expected(s, "`<count> <color>`").within(sample, s))?;
+                let count = count.parse::<u32>().map_err(|_| ParseError::at(sample, count, "cube count"))?;
+                let color = palette.resolve(color).ok_or_else(|| ParseError::at(sample, color, palette.describe()))?;
                 Ok(result.add(count, color))
             })
     }
 }
 
+/// The cubes in the bag, which also decides what colors part 1 accepts.
+#[derive(Debug, PartialEq, Eq)]
+struct Bag {
+    palette: Palette,
+    cubes: Sample,
+}
+
+impl Bag {
+    const DEFAULT: &'static str = "red=12,green=13,blue=14";
+
+    /// Parses a specification like `red=12,green=13,blue=14`.
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut palette = Palette::default();
+        let mut cubes = Sample::default();
+        for item in spec.split(',') {
+            let (name, count) = item.split_once('=')
+                .filter(|(name, _)| !name.is_empty())
+                .ok_or_else(|| format!("bad bag {:?}, expected <color>=<count> like {}", item, Self::DEFAULT))?;
+            let count = count.parse::<u32>().map_err(|e| format!("bad count for {} in bag: {}", name, e))?;
+            if palette.id(name).is_some() {
+                return Err(format!("bad bag {:?}, {} is given more than once", spec, name));
+            }
+            cubes = cubes.add(count, palette.intern(name));
+        }
+        Ok(Self { palette, cubes })
+    }
+
+    /// The bag from `--bag`, or 12 red, 13 green and 14 blue cubes.
+    fn from_options() -> Result<Self, String> {
+        Self::parse(advent_of_code::option("bag").unwrap_or(Self::DEFAULT))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct Game {
     id: u32,
@@ -85,42 +142,44 @@ impl Game {
     fn cubes_reqiured(&self) -> Sample {
         self.samples.iter().fold(Sample::default(), |r, s| s.max_by_color(&r))
     }
-}
 
-impl FromStr for Game {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, id, rest) = regex_captures!(r"Game\s+([0-9]+):\s+(.*)$", s).ok_or(ParseError)?;
-        let id = id.parse::<u32>().map_err(|_| ParseError)?;
-        let samples = rest.split(';').filter_map(|s| s.parse::<Sample>().ok()).collect_vec();
+    fn parse(s: &str, palette: &mut Palette) -> Result<Self, ParseError> {
+        let (_, id, rest) = regex_captures!(r"Game\s+([0-9]+):\s+(.*)$", s)
+            .ok_or_else(|| ParseError::expected(s, "`Game <id>: <samples>`"))?;
+        let id = id.parse::<u32>().map_err(|_| ParseError::at(s, id, "game id"))?;
+        let samples = rest.split(';')
+            .map(|sample| Sample::parse(sample, palette).map_err(|e| e.within(s, sample)))
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Game{id, samples})
     }
 }
 
-fn part1(input: Lines) -> String {
-    let cubes = Sample{ colors: [12, 13, 14]};
-    input
-        .map(|line| line.parse::<Game>().unwrap())
-        .filter(|g| g.is_possible(&cubes))
+fn parse_games(input: Lines, palette: &mut Palette) -> Result<Vec<Game>, ParseError> {
+    input.parse_lines(|line| Game::parse(line, palette)).collect()
+}
+
+fn part1(input: Lines) -> Result<u32, Box<dyn Error>> {
+    let mut bag = Bag::from_options()?;
+    Ok(parse_games(input, &mut bag.palette)?
+        .into_iter()
+        .filter(|g| g.is_possible(&bag.cubes))
         .map(|g| g.id)
-        .sum::<u32>()
-        .to_string()
+        .sum::<u32>())
 }
 
-fn part2(input: Lines) -> String {
-    input
-        .map(|line| line.parse::<Game>().unwrap())
-        .map(|g| g.cubes_reqiured().power())
-        .sum::<u32>()
-        .to_string()
+fn part2(input: Lines) -> Result<u32, Box<dyn Error>> {
+    // the fewest cubes needed don't depend on the bag, so any color goes
+    let mut palette = Palette::open();
+    Ok(parse_games(input, &mut palette)?
+        .into_iter()
+        .map(|g| g.cubes_reqiured().power(&palette))
+        .sum::<u32>())
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2; options = ["bag"]);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -129,24 +188,57 @@ mod tests {
     use advent_of_code::verify;
 
     #[test]
-    fn test_from_str() {
+    fn test_parse() {
+        let mut bag = Bag::parse(Bag::DEFAULT).unwrap();
+        let sample = |counts: [u32; 3]| {
+            counts.into_iter().enumerate().filter(|&(_, n)| n > 0).fold(Sample::default(), |s, (c, n)| s.add(n, c))
+        };
         assert_eq!(
-            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".parse::<Game>(),
+            Game::parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green", &mut bag.palette),
             Ok(Game{
                 id: 1,
                 samples: vec![
-                    Sample{colors: [4, 0, 3]},
-                    Sample{colors: [1, 2, 6]},
-                    Sample{colors: [0, 2, 0]}
+                    sample([4, 0, 3]),
+                    sample([1, 2, 6]),
+                    sample([0, 2, 0])
                 ]
             })
         )
     }
 
+    #[test]
+    fn test_bag() {
+        let mut bag = Bag::parse("red=1,yellow=2").unwrap();
+        assert_eq!(bag.palette.describe(), "red or yellow");
+        let game = Game::parse("Game 7: 2 yellow; 1 red, 1 yellow", &mut bag.palette).unwrap();
+        assert!(game.is_possible(&bag.cubes));
+        assert_eq!(game.cubes_reqiured().power(&bag.palette), 2);
+        assert!(Bag::parse("red=many").is_err());
+        assert!(Bag::parse("red").is_err());
+        assert_eq!(Bag::parse("red=1,blue=1,red=2"), Err("bad bag \"red=1,blue=1,red=2\", red is given more than once".to_string()));
+    }
+
     #[test]
     fn example() {
         let input = include_str!("example.txt");
         verify!(part1, input, "8");
         verify!(part2, input, "2286");
     }
+
+    #[test]
+    fn extra_colors() {
+        let input = "Game 1: 1 red, 2 green, 3 blue; 4 purple\nGame 2: 1 purple, 2 red, 2 green, 2 blue";
+        verify!(part1, input, Err("line 1, column 35: expected red, green or blue"));
+        verify!(part2, input, "32");
+    }
+
+    #[test]
+    fn invalid_game() {
+        let input = "Game 1: 3 blue\nGame two: 1 red";
+        verify!(part1, input, Err("line 2, column 1: expected `Game <id>: <samples>`"));
+        let input = "Game 1: 3 blue\nGame 2: 1 red, 2 purple; 3 green";
+        verify!(part1, input, Err("line 2, column 18: expected red, green or blue"));
+        let input = "Game 1: 3 blue; blue";
+        verify!(part2, input, Err("line 1, column 16: expected `<count> <color>`"));
+    }
 }
@@ -1,22 +1,22 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{day, grid::{Grid, Pos}, Day, ParseError, Runner};
 use itertools::Itertools;
-use std::str::Lines;
+use std::{process::ExitCode, str::Lines};
 
 struct Schematic {
-    cells: Vec<Vec<char>>,
+    cells: Grid<char>,
 }
 
 impl Schematic {
-    fn parse(input: Lines) -> Self {
-        let cells = input.map(|line| line.chars().collect_vec()).collect_vec();
-        Schematic { cells }
+    fn parse(input: Lines) -> Result<Self, ParseError> {
+        let cells = Grid::parse(input, |c| c)?;
+        Ok(Schematic { cells })
     }
 
     fn part_numbers(&self) -> Vec<PartNumber> {
-        self.cells.iter().enumerate().flat_map(|(row, row_chars)| {
+        (0..self.cells.rows()).flat_map(|row| {
             let mut part_numbers: Vec<PartNumber> = Vec::new();
             let mut part_number: Option<PartNumber> = None;
-            for (col, c) in row_chars.iter().enumerate() {
+            for (col, c) in self.cells.row(row).iter().enumerate() {
                 part_number = match (part_number, c.to_digit(10)) {
                     (None, Some(digit)) => Some(PartNumber {num: digit, row, col_begin: col, col_end: col}),
                     (None, None) => None,
@@ -34,48 +34,23 @@ impl Schematic {
     }
 
     fn is_adjacent_to_symbol(&self, part_number: &PartNumber) -> bool {
-        let row = part_number.row;
-        let cb = part_number.col_begin;
-        let cb = if cb > 0 {
-            if self.is_symbol_at(row, cb - 1) {return true}
-            cb - 1
-        } else {
-            cb
-        };
-
-        let ce = part_number.col_end + 1;
-        let ce = if ce < self.cells[row].len() {
-            if self.is_symbol_at(row, ce) {return true}
-            ce + 1
-        } else {
-            ce
-        };
-
-        if row > 0 && self.cells[row-1][cb..ce].iter().any(|&c| Self::is_symbol(c)) {return true}
-
-        if (row+1) < self.cells.len() && self.cells[row+1][cb..ce].iter().any(|&c| Self::is_symbol(c)) {return true}
-
-        false
+        let rows = part_number.row.saturating_sub(1)..part_number.row + 2;
+        let cols = part_number.col_begin.saturating_sub(1)..part_number.col_end + 2;
+        self.cells.region(rows, cols).any(|(_, &c)| Self::is_symbol(c))
     }
 
     fn is_symbol(c: char) -> bool {
-        c != '.' && !c.is_digit(10)
+        c != '.' && !c.is_ascii_digit()
     }
 
-    fn is_symbol_at(&self, row: usize, col: usize) -> bool {
-        Self::is_symbol(self.cells[row][col])
-    }
-
-    fn find_gears(&self) -> Vec<(usize, usize)> {
-        self.cells.iter().enumerate().flat_map(|(row, row_chars)| {
-            row_chars.iter().enumerate().filter_map(|(col, &c)| {
-                if c == '*' {Some((row, col))} else {None}
-            }).collect_vec()
+    fn find_gears(&self) -> Vec<Pos> {
+        self.cells.iter().filter_map(|(pos, &c)| {
+            if c == '*' {Some(pos)} else {None}
         }).collect_vec()
     }
 
-    fn is_adjacent(&self, part_number: &PartNumber, symbol: (usize, usize)) -> bool {
-        let (row, col) = symbol;
+    fn is_adjacent(&self, part_number: &PartNumber, symbol: Pos) -> bool {
+        let Pos { row, col } = symbol;
         row.abs_diff(part_number.row) <= 1 && (col + 1 >= part_number.col_begin) && (col <= part_number.col_end + 1)
     }
 }
@@ -87,20 +62,19 @@ struct PartNumber {
     col_end: usize,
 }
 
-fn part1(input: Lines) -> String {
-    let schematic = Schematic::parse(input);
-    schematic.part_numbers()
+fn part1(input: Lines) -> Result<u32, ParseError> {
+    let schematic = Schematic::parse(input)?;
+    Ok(schematic.part_numbers()
         .into_iter()
         .filter(|pn| schematic.is_adjacent_to_symbol(pn))
         .map(|pn| pn.num)
-        .sum::<u32>()
-        .to_string()
+        .sum::<u32>())
 }
 
-fn part2(input: Lines) -> String {
-    let schematic = Schematic::parse(input);
+fn part2(input: Lines) -> Result<u32, ParseError> {
+    let schematic = Schematic::parse(input)?;
     let part_numbers = schematic.part_numbers();
-    schematic.find_gears()
+    Ok(schematic.find_gears()
         .into_iter()
         .map(|gear_pos| {
             part_numbers.iter()
@@ -109,15 +83,13 @@ fn part2(input: Lines) -> String {
         })
         .filter(|adjacent_part_numbers| adjacent_part_numbers.len() == 2)
         .map(|adjacent_part_numbers| adjacent_part_numbers.iter().map(|pn| pn.num).product::<u32>())
-        .sum::<u32>()
-        .to_string()
+        .sum::<u32>())
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -1,10 +1,7 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{day, Day, ParseError, ParseLines, Runner};
 use itertools::Itertools;
 use lazy_regex::regex_captures;
-use std::str::{FromStr, Lines};
-
-#[derive(Debug, PartialEq, Eq)]
-struct ParseError;
+use std::{process::ExitCode, str::{FromStr, Lines}};
 
 #[derive(Default, Debug, PartialEq, Eq)]
 struct Card {
@@ -17,8 +14,9 @@ impl FromStr for Card {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, id, winning, numbers) = regex_captures!(r"Card\s+([0-9]+):\s*(.*)\s*\|\s*(.*)\s*$", s).ok_or(ParseError)?;
-        let id = id.parse::<u32>().map_err(|_| ParseError)?;
+        let (_, id, winning, numbers) = regex_captures!(r"Card\s+([0-9]+):\s*(.*)\s*\|\s*(.*)\s*$", s)
+            .ok_or_else(|| ParseError::expected(s, "`Card <id>: <winning> | <numbers>`"))?;
+        let id = id.parse::<u32>().map_err(|_| ParseError::at(s, id, "card id"))?;
         let winning = winning.split_whitespace().filter_map(|n| n.parse::<u8>().ok()).collect_vec();
         let numbers = numbers.split_whitespace().filter_map(|n| n.parse::<u8>().ok()).collect_vec();
         Ok(Card{id, winning, numbers})
@@ -36,17 +34,20 @@ impl Card {
     }
 }
 
-fn part1(input: Lines) -> String {
-    input.into_iter()
-        .map(|line| line.parse::<Card>().unwrap())
+fn parse_cards(input: Lines) -> Result<Vec<Card>, ParseError> {
+    input.parse_lines(str::parse::<Card>).collect()
+}
+
+fn part1(input: Lines) -> Result<u32, ParseError> {
+    Ok(parse_cards(input)?
+        .into_iter()
         .map(|card| card.worth())
-        .sum::<u32>()
-        .to_string()
+        .sum::<u32>())
 }
 
-fn part2(input: Lines) -> String {
-    let matching = input.into_iter()
-        .map(|line| line.parse::<Card>().unwrap())
+fn part2(input: Lines) -> Result<usize, ParseError> {
+    let matching = parse_cards(input)?
+        .into_iter()
         .map(|card| card.num_winning())
         .collect_vec();
     let mut cards = vec![1usize; matching.len()];
@@ -55,14 +56,13 @@ fn part2(input: Lines) -> String {
             cards[i+j] += cards[i];
         }
     }
-    cards.into_iter().sum::<usize>().to_string()
+    Ok(cards.into_iter().sum::<usize>())
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
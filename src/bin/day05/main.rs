@@ -1,10 +1,7 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{day, interval::{Interval, IntervalSet, PiecewiseShift}, log, timed_parse, Day, ParseError, ParseErrors, Runner};
 use itertools::Itertools;
 use lazy_regex::regex_captures;
-use std::{str::{Lines, FromStr}, ops};
-
-#[derive(Debug, PartialEq, Eq)]
-struct ParseError;
+use std::{str::{Lines, FromStr}, collections::{HashMap, VecDeque}, error::Error, fmt, process::ExitCode};
 
 #[derive(Clone, PartialEq, Debug)]
 struct CategoryRange {
@@ -17,43 +14,24 @@ impl CategoryRange {
         Self { start, length }
     }
 
-    fn new_end(start: i64, end: i64) -> Self {
-        Self { start, length: end - start }
-    }
-
     fn end(&self) -> i64 {
         self.start + self.length
     }
 
-    fn is_empty(&self) -> bool {
-        self.length <= 0
-    }
-
-    fn non_empty(self) -> Option<Self> {
-        Some(self).filter(|r| !r.is_empty())
-    }
-
     fn contains(&self, value: i64) -> bool {
         value >= self.start && value < self.end()
     }
+}
 
-    fn intersect(&self, other: &Self) -> Option<Self> {
-        let start = self.start.max(other.start);
-        let end = (self.end()).min(other.end());
-        if start < end {
-            let length = end - start;
-            Some(Self{ start, length })
-        } else {
-            None
-        }
+impl From<&CategoryRange> for Interval<i64> {
+    fn from(range: &CategoryRange) -> Self {
+        Interval::with_len(range.start, range.length)
     }
 }
 
-impl ops::Add<i64> for CategoryRange {
-    type Output = Self;
-
-    fn add(self, rhs: i64) -> Self::Output {
-        Self{ start: self.start + rhs, length: self.length }
+impl From<Interval<i64>> for CategoryRange {
+    fn from(interval: Interval<i64>) -> Self {
+        Self::new(interval.start, interval.len())
     }
 }
 
@@ -64,22 +42,16 @@ struct CategoryMapEntry {
 }
 
 impl CategoryMapEntry {
- 
     fn dest_range(&self) -> CategoryRange {
-        CategoryRange { start: self.dest_range_start, length: self.source_range.length }
+        CategoryRange::new(self.dest_range_start, self.source_range.length)
     }
 
-    fn map(&self, value: i64) -> i64 {
-        value - self.source_range.start + self.dest_range_start
+    fn offset(&self) -> i64 {
+        self.dest_range_start - self.source_range.start
     }
 
-    fn map_range(&self, range: &CategoryRange) -> Option<Self> {
-        self.source_range
-            .intersect(range)
-            .map(|source_range| {
-                let dest_range_start = self.map(source_range.start);
-                Self { source_range, dest_range_start }
-            })
+    fn map(&self, value: i64) -> i64 {
+        value + self.offset()
     }
 }
 
@@ -87,23 +59,93 @@ impl FromStr for CategoryMapEntry {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, dest_range_start, source_range_start, range_length) = regex_captures!(r"(\d+)\s+(\d+)\s+(\d+)", s).ok_or(ParseError)?;
-        let dest_range_start = dest_range_start.parse::<i64>().map_err(|_| ParseError)?;
-        let source_range_start = source_range_start.parse::<i64>().map_err(|_| ParseError)?;
-        let range_length = range_length.parse::<i64>().map_err(|_| ParseError)?;
+        let (_, dest_range_start, source_range_start, range_length) = regex_captures!(r"(\d+)\s+(\d+)\s+(\d+)", s)
+            .ok_or_else(|| ParseError::expected(s, "`<destination start> <source start> <length>`"))?;
+        let parse = |n: &str, expected| n.parse::<i64>().map_err(|_| ParseError::at(s, n, expected));
+        let dest_range_start = parse(dest_range_start, "destination range start")?;
+        let source_range_start = parse(source_range_start, "source range start")?;
+        let range_length = parse(range_length, "range length")?;
         let source_range = CategoryRange { start: source_range_start, length: range_length };
         Ok(CategoryMapEntry { source_range, dest_range_start })
     }
 }
 
+/// A problem with the entries of a map, referring to entries by their index
+/// in the order they were listed.
+#[derive(Debug, PartialEq, Eq)]
+enum MapDiagnostic {
+    /// the entry maps no values
+    ZeroLength(usize),
+    /// the end of the entry's source or destination range doesn't fit in an i64
+    Overflow(usize),
+    /// both entries map some of the same source values
+    OverlappingSources(usize, usize),
+    /// both entries map to some of the same destination values, so the map
+    /// isn't injective
+    OverlappingDestinations(usize, usize),
+}
+
+impl MapDiagnostic {
+    /// Whether lookups are ambiguous or overflow. Overlapping destinations
+    /// are fine for lookups, and only stop the map from being inverted.
+    fn is_error(&self) -> bool {
+        !matches!(self, Self::OverlappingDestinations(..))
+    }
+
+    /// Converts to an error on the line of the entry that's the problem,
+    /// given the lines and line numbers of all the entries.
+    fn to_parse_error(&self, lines: &[(&str, usize)]) -> ParseError {
+        let (entry, expected) = match *self {
+            Self::ZeroLength(i) => (i, String::from("range length greater than 0")),
+            Self::Overflow(i) => (i, format!("range ending at most {}", i64::MAX)),
+            Self::OverlappingSources(i, j) => (j, format!("source range not overlapping line {}", lines[i].1)),
+            Self::OverlappingDestinations(i, j) => (j, format!("destination range not overlapping line {}", lines[i].1)),
+        };
+        let (line, n) = lines[entry];
+        ParseError::expected(line, expected).at_line(n)
+    }
+}
+
 struct CategoryMap {
     entries: Vec<CategoryMapEntry>,
+    /// the entries as shifts, built once since every range lookup uses it
+    shift: PiecewiseShift<i64>,
 }
 
 impl CategoryMap {
+    /// Finds entries which would make lookups ambiguous or overflow, or which
+    /// stop the map from being inverted. Lookups are only well defined for
+    /// entries without any [MapDiagnostic::is_error] diagnostics.
+    fn validate(entries: &[CategoryMapEntry]) -> Vec<MapDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut valid = Vec::new();
+        for (i, e) in entries.iter().enumerate() {
+            let length = e.source_range.length;
+            if length <= 0 {
+                diagnostics.push(MapDiagnostic::ZeroLength(i));
+            } else if e.source_range.start.checked_add(length).is_none() || e.dest_range_start.checked_add(length).is_none() {
+                diagnostics.push(MapDiagnostic::Overflow(i));
+            } else {
+                valid.push(i);
+            }
+        }
+        for (&i, &j) in valid.iter().tuple_combinations() {
+            let (a, b) = (&entries[i], &entries[j]);
+            if Interval::from(&a.source_range).intersect(&(&b.source_range).into()).is_some() {
+                diagnostics.push(MapDiagnostic::OverlappingSources(i, j));
+            }
+            if Interval::from(&a.dest_range()).intersect(&(&b.dest_range()).into()).is_some() {
+                diagnostics.push(MapDiagnostic::OverlappingDestinations(i, j));
+            }
+        }
+        diagnostics
+    }
+
+    /// Expects entries without errors from [CategoryMap::validate].
     fn from_entries(mut entries: Vec<CategoryMapEntry>) -> Self {
         entries.sort_by_key(|e| e.source_range.start);
-        Self {entries}
+        let shift = PiecewiseShift::new(entries.iter().map(|e| ((&e.source_range).into(), e.offset())));
+        Self {entries, shift}
     }
 
     fn lookup(&self, value: i64) -> i64 {
@@ -115,92 +157,285 @@ impl CategoryMap {
     }
 
     fn lookup_range(&self, range: &CategoryRange) -> Vec<CategoryRange> {
-        let (end, mut output) = self.entries.iter()
-            .filter_map(|e| e.map_range(range))
-            .fold((range.start, Vec::<CategoryRange>::new()), |(start, mut output), e| {
-                output.extend(CategoryRange::new_end(start, e.source_range.start).non_empty());
-                output.push(e.dest_range());
-                (e.source_range.end(), output)
-            });
-        output.extend(CategoryRange::new_end(end, range.end()).non_empty());
-        output
+        self.shift
+            .apply_interval(range.into())
+            .into_iter()
+            .map(CategoryRange::from)
+            .collect_vec()
     }
 
     fn lookup_ranges(&self, ranges: &[CategoryRange]) -> Vec<CategoryRange> {
         ranges.iter().flat_map(|r| self.lookup_range(r)).collect_vec()
     }
+
+    /// The single map equivalent to looking up in `self` and then `other`.
+    fn compose(&self, other: &CategoryMap) -> CategoryMap {
+        let entries = self.shift
+            .compose(&other.shift)
+            .pieces()
+            .iter()
+            .map(|&(source, offset)| CategoryMapEntry {
+                source_range: source.into(),
+                dest_range_start: source.start + offset,
+            })
+            .collect_vec();
+        CategoryMap::from_entries(entries)
+    }
+
+    fn source_set(&self) -> IntervalSet<i64> {
+        self.entries.iter().map(|e| Interval::from(&e.source_range)).collect()
+    }
+
+    fn dest_set(&self) -> IntervalSet<i64> {
+        self.entries.iter().map(|e| Interval::from(&e.dest_range())).collect()
+    }
+
+    /// The map from destination back to source, if every destination has
+    /// exactly one source. That's the case when the destination ranges don't
+    /// overlap and cover the same values as the source ranges, so that values
+    /// outside them map to themselves in both directions.
+    fn invert(&self) -> Option<CategoryMap> {
+        let dest_len: i64 = self.entries.iter().map(|e| e.source_range.length).sum();
+        let dest_set = self.dest_set();
+        if dest_set != self.source_set() || dest_set.count() != dest_len {
+            return None;
+        }
+        let entries = self.entries.iter()
+            .map(|e| CategoryMapEntry { source_range: e.dest_range(), dest_range_start: e.source_range.start })
+            .collect_vec();
+        Some(CategoryMap::from_entries(entries))
+    }
+
+    /// Every source value mapping into `range`.
+    fn reverse_lookup_range(&self, range: &CategoryRange) -> IntervalSet<i64> {
+        let range = IntervalSet::from_iter([Interval::from(range)]);
+        let unmapped = range.difference(&self.source_set());
+        self.entries.iter()
+            .flat_map(|e| {
+                let dest = IntervalSet::from_iter([Interval::from(&e.dest_range())]);
+                range.intersection(&dest)
+                    .intervals()
+                    .iter()
+                    .map(|i| Interval::new(i.start - e.offset(), i.end - e.offset()))
+                    .collect_vec()
+            })
+            .chain(unmapped.intervals().iter().copied())
+            .collect()
+    }
+
+    /// Collapses a chain of maps, such as seed to location, into one.
+    fn compose_all<'a, I: IntoIterator<Item = &'a CategoryMap>>(maps: I) -> CategoryMap {
+        maps.into_iter().fold(CategoryMap::from_entries(Vec::new()), |composed, map| composed.compose(map))
+    }
 }
 
-fn parse_seeds(seeds: &str) -> Vec<i64> {
-    let seeds = seeds.split_once(':').unwrap().1;
-    seeds.split_whitespace().map(|s| s.parse::<i64>().unwrap()).collect_vec()
+/// Writes the entries in the same `<destination start> <source start> <length>`
+/// form as the almanac.
+impl fmt::Display for CategoryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for e in self.entries.iter() {
+            writeln!(f, "{} {} {}", e.dest_range_start, e.source_range.start, e.source_range.length)?;
+        }
+        Ok(())
+    }
 }
 
-fn parse_maps(lines: Lines) -> Vec<CategoryMap> {
-    lines
-        .group_by(|line| !line.is_empty())
-        .into_iter()
-        .filter_map(|(non_empty, lines)| {
-            if non_empty {Some(lines.into_iter().dropping(1))} else {None}
-        })
-        .map(|lines| {
-            CategoryMap::from_entries(lines.map(|line| line.parse::<CategoryMapEntry>().unwrap()).collect_vec())
-        })
-        .collect_vec()
+fn parse_seeds(line: &str) -> Result<Vec<i64>, ParseError> {
+    let seeds = line.strip_prefix("seeds:").ok_or_else(|| ParseError::expected(line, "`seeds: <numbers>`"))?;
+    let seeds = seeds
+        .split_whitespace()
+        .map(|s| s.parse::<i64>().map_err(|_| ParseError::at(line, s, "seed number")))
+        .collect::<Result<Vec<_>, _>>()?;
+    if seeds.is_empty() {
+        return Err(ParseError::new(line, line.len()..line.len(), "seed number"));
+    }
+    Ok(seeds)
 }
 
-fn parse_part1(mut lines: Lines) -> (Vec<i64>, Vec<CategoryMap>) {
-    let seeds = parse_seeds(lines.next().unwrap());
-    let maps = parse_maps(lines);
-    (seeds, maps)
+/// A map between two named categories, from a header like `seed-to-soil map:`
+struct NamedMap {
+    source: String,
+    destination: String,
+    map: CategoryMap,
 }
 
-fn part1(input: Lines) -> String {
-    let (seeds, maps) = parse_part1(input);
-    seeds.into_iter().map(|s| {
-        maps.iter().fold(s, |value, category_map| {
-            category_map.lookup(value)
-        })
-    })
-    .min()
-    .unwrap()
-    .to_string()
+struct Almanac {
+    /// in the order listed, where each map's source is the previous map's destination
+    maps: Vec<NamedMap>,
 }
 
-fn parse_seed_ranges(seeds: &str) -> Vec<CategoryRange> {
-    let seeds = seeds.split_once(':').unwrap().1;
-    seeds
-        .split_whitespace()
-        .map(|x| x.parse::<i64>().unwrap())
-        .chunks(2)
+impl Almanac {
+    /// The maps leading from the `source` category to `destination`, found by
+    /// searching the graph of categories connected by maps.
+    fn chain(&self, source: &str, destination: &str) -> Result<Vec<&CategoryMap>, String> {
+        // category -> the map reaching it in the search
+        let mut reached: HashMap<&str, Option<&NamedMap>> = HashMap::from([(source, None)]);
+        let mut queue = VecDeque::from([source]);
+        while let Some(category) = queue.pop_front() {
+            for m in self.maps.iter().filter(|m| m.source == category) {
+                if !reached.contains_key(m.destination.as_str()) {
+                    reached.insert(&m.destination, Some(m));
+                    queue.push_back(&m.destination);
+                }
+            }
+        }
+        if !reached.contains_key(destination) {
+            return Err(format!("no chain of maps from {} to {}", source, destination));
+        }
+        let mut chain = Vec::new();
+        let mut category = destination;
+        while let Some(m) = reached[category] {
+            chain.push(&m.map);
+            category = &m.source;
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// The single map from the `source` category to `destination`.
+    fn map_between(&self, source: &str, destination: &str) -> Result<CategoryMap, String> {
+        Ok(CategoryMap::compose_all(self.chain(source, destination)?))
+    }
+}
+
+fn parse_map_header(header: &str) -> Result<(String, String), ParseError> {
+    let (_, source, destination) = regex_captures!(r"^(\w+)-to-(\w+) map:$", header)
+        .ok_or_else(|| ParseError::expected(header, "`<source>-to-<destination> map:`"))?;
+    Ok((source.to_string(), destination.to_string()))
+}
+
+/// Parses the maps following the seeds line, which is line 1 of the input.
+/// Reports every invalid map entry, while overlapping destinations are only
+/// logged as a warning.
+fn parse_maps(lines: Lines) -> Result<Almanac, ParseErrors> {
+    let mut maps: Vec<NamedMap> = Vec::new();
+    let mut errors = Vec::new();
+    for (non_empty, lines) in lines.zip(2..).group_by(|(line, _)| !line.is_empty()).into_iter() {
+        if !non_empty {
+            continue;
+        }
+        let mut lines = lines.collect_vec();
+        let (header, header_n) = lines.remove(0);
+        let (source, destination) = parse_map_header(header).map_err(|e| e.at_line(header_n))?;
+        if let Some(previous) = maps.last().filter(|m| m.destination != source) {
+            let expected = format!("map from {} following the {} to {} map", previous.destination, previous.source, previous.destination);
+            return Err(ParseError::at(header, &header[..source.len()], expected).at_line(header_n).into());
+        }
+        let entries = lines.iter()
+            .map(|(line, n)| line.parse::<CategoryMapEntry>().map_err(|e| e.at_line(*n)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let errors_before = errors.len();
+        for diagnostic in CategoryMap::validate(&entries) {
+            let error = diagnostic.to_parse_error(&lines);
+            if diagnostic.is_error() {
+                errors.push(error);
+            } else {
+                log!(Warn, "{}", error);
+            }
+        }
+        // an invalid map is left empty, only keeping its categories to check the next map follows it
+        let entries = if errors.len() > errors_before { Vec::new() } else { entries };
+        maps.push(NamedMap { source, destination, map: CategoryMap::from_entries(entries) });
+    }
+    if errors.is_empty() {
+        Ok(Almanac { maps })
+    } else {
+        Err(ParseErrors(errors))
+    }
+}
+
+fn parse_part1(mut lines: Lines) -> Result<(Vec<i64>, Almanac), ParseErrors> {
+    let seeds = parse_seeds(lines.next().unwrap_or_default()).map_err(|e| e.at_line(1))?;
+    let almanac = parse_maps(lines)?;
+    Ok((seeds, almanac))
+}
+
+fn part1(input: Lines) -> Result<i64, Box<dyn Error>> {
+    let (seeds, almanac) = timed_parse(|| parse_part1(input))?;
+    let seed_to_location = almanac.map_between("seed", "location")?;
+    Ok(seeds.into_iter().map(|s| seed_to_location.lookup(s)).min().unwrap())
+}
+
+fn parse_seed_ranges(line: &str) -> Result<Vec<CategoryRange>, ParseError> {
+    let seeds = parse_seeds(line)?;
+    if seeds.len() % 2 != 0 {
+        return Err(ParseError::new(line, line.len()..line.len(), "range length after last seed"));
+    }
+    Ok(seeds
+        .into_iter()
+        .tuples()
+        .map(|(start, length)| CategoryRange::new(start, length))
+        .collect_vec())
+}
+
+fn parse_part2(mut lines: Lines) -> Result<(Vec<CategoryRange>, Almanac), ParseErrors> {
+    let seeds = parse_seed_ranges(lines.next().unwrap_or_default()).map_err(|e| e.at_line(1))?;
+    let almanac = parse_maps(lines)?;
+    Ok((seeds, almanac))
+}
+
+fn part2(input: Lines) -> Result<i64, Box<dyn Error>> {
+    let (seed_ranges, almanac) = timed_parse(|| parse_part2(input))?;
+    let seed_to_location = almanac.map_between("seed", "location")?;
+    Ok(seed_to_location.lookup_ranges(&seed_ranges)
         .into_iter()
-        .map(|mut c| CategoryRange::new(c.next().unwrap(), c.next().unwrap()))
+        .map(|r| r.start)
+        .min()
+        .ok_or("no seeds in any range")?)
+}
+
+fn seed_set(seed_ranges: &[CategoryRange]) -> IntervalSet<i64> {
+    seed_ranges.iter().map(Interval::from).collect()
+}
+
+/// The seed ranges within `seed_ranges` which reach locations in `locations`.
+fn seeds_reaching(seed_to_location: &CategoryMap, seed_ranges: &[CategoryRange], locations: &CategoryRange) -> Vec<CategoryRange> {
+    seed_to_location
+        .reverse_lookup_range(locations)
+        .intersection(&seed_set(seed_ranges))
+        .intervals()
+        .iter()
+        .map(|&i| CategoryRange::from(i))
         .collect_vec()
 }
 
-fn parse_part2(mut lines: Lines) -> (Vec<CategoryRange>, Vec<CategoryMap>) {
-    let seeds = parse_seed_ranges(lines.next().unwrap());
-    let maps = parse_maps(lines);
-    (seeds, maps)
+/// Lists every seed range which reaches the lowest location.
+fn min_location_seeds(input: Lines) -> Result<String, Box<dyn Error>> {
+    let (seed_ranges, almanac) = timed_parse(|| parse_part2(input))?;
+    let seed_to_location = almanac.map_between("seed", "location")?;
+    let min_location = seed_to_location.lookup_ranges(&seed_ranges)
+        .into_iter()
+        .map(|r| r.start)
+        .min()
+        .ok_or("no seeds in any range")?;
+    Ok(seeds_reaching(&seed_to_location, &seed_ranges, &CategoryRange::new(min_location, 1))
+        .into_iter()
+        .map(|r| format!("{}..{}", r.start, r.end()))
+        .join(" "))
 }
 
-fn part2(input: Lines) -> String {
-    let (seed_ranges, maps) = parse_part2(input);
-    maps.iter().fold(seed_ranges, |ranges, category_map| {
-        category_map.lookup_ranges(&ranges)
-    })
-    .into_iter()
-    .map(|r| r.start)
-    .min()
-    .unwrap()
-    .to_string()
+/// Cross-checks part2 by searching locations in increasing order for the
+/// first one which any seed reaches, using the inverse of the seed to
+/// location map.
+fn part2_reverse(input: Lines) -> Result<i64, Box<dyn Error>> {
+    let (seed_ranges, almanac) = timed_parse(|| parse_part2(input))?;
+    let location_to_seed = almanac.map_between("seed", "location")?.invert().ok_or("seed to location map is not invertible")?;
+    let seeds = seed_set(&seed_ranges);
+    location_to_seed.shift
+        .split(Interval::new(0, i64::MAX))
+        .into_iter()
+        .find_map(|(locations, offset)| {
+            // within each part seeds increase with locations, so the lowest seed has the lowest location
+            let reached = IntervalSet::from_iter([Interval::new(locations.start + offset, locations.end + offset)]);
+            reached.intersection(&seeds).min().map(|seed| seed - offset)
+        })
+        .ok_or_else(|| "no seed reaches any location".into())
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2, part2_reverse, min_location_seeds);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -212,42 +447,75 @@ mod tests {
     fn test_parse_seed_ranges() {
         assert_eq!(
             parse_seed_ranges("seeds: 79 14 55 13"),
-            vec![CategoryRange::new(79, 14), CategoryRange::new(55, 13)]
-        )
-    }
-
-    #[test]
-    fn test_intersect() {
-        assert_eq!(
-            CategoryRange::new(1, 5).intersect(&CategoryRange::new(2, 3)),
-            Some(CategoryRange::new(2, 3))
+            Ok(vec![CategoryRange::new(79, 14), CategoryRange::new(55, 13)])
         );
         assert_eq!(
-            CategoryRange::new(2, 3).intersect(&CategoryRange::new(1, 5)),
-            Some(CategoryRange::new(2, 3))
-        );
-        assert_eq!(
-            CategoryRange::new(1, 3).intersect(&CategoryRange::new(2, 5)),
-            Some(CategoryRange::new(2, 2))
-        );
-        assert_eq!(
-            CategoryRange::new(2, 5).intersect(&CategoryRange::new(1, 3)),
-            Some(CategoryRange::new(2, 2))
+            parse_seed_ranges("seeds: 79 14 55").map_err(|e| e.expected),
+            Err(String::from("range length after last seed"))
         );
+    }
+
+    /// Deterministic pseudo-random numbers, to generate maps for testing.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn below(&mut self, n: i64) -> i64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as i64
+        }
+
+        fn map(&mut self) -> CategoryMap {
+            let entries = (0..self.below(4)).map(|_| {
+                let source_range = CategoryRange::new(self.below(100), self.below(30));
+                CategoryMapEntry { source_range, dest_range_start: self.below(100) }
+            });
+            // drop overlapping entries, which the almanac doesn't contain
+            let mut entries = entries.sorted_by_key(|e| e.source_range.start).collect_vec();
+            entries.dedup_by(|b, a| b.source_range.start < a.source_range.end());
+            CategoryMap::from_entries(entries)
+        }
+    }
+
+    #[test]
+    fn test_compose_matches_sequential_lookup() {
+        let mut rng = XorShift(0x2023_1205);
+        for _ in 0..200 {
+            let maps = (0..1 + rng.below(4)).map(|_| rng.map()).collect_vec();
+            let composed = CategoryMap::compose_all(&maps);
+            for value in -10..150 {
+                let sequential = maps.iter().fold(value, |v, m| m.lookup(v));
+                assert_eq!(composed.lookup(value), sequential, "value {} maps:\n{}", value, maps.iter().join("\n"));
+            }
+            let ranges = vec![CategoryRange::new(rng.below(100), rng.below(50))];
+            let sequential = maps.iter().fold(ranges.clone(), |r, m| m.lookup_ranges(&r));
+            let as_set = |ranges: Vec<CategoryRange>| ranges.iter().map(Interval::from).collect::<IntervalSet<_>>();
+            assert_eq!(as_set(composed.lookup_ranges(&ranges)), as_set(sequential));
+        }
+    }
+
+    #[test]
+    fn test_compose_display() {
+        let seed_to_soil = CategoryMap::from_entries(vec![
+            CategoryMapEntry{ source_range: CategoryRange::new(98, 2), dest_range_start: 50},
+            CategoryMapEntry{ source_range: CategoryRange::new(50, 48), dest_range_start: 52},
+        ]);
+        let soil_to_fertilizer = CategoryMap::from_entries(vec![
+            CategoryMapEntry{ source_range: CategoryRange::new(52, 2), dest_range_start: 0},
+        ]);
         assert_eq!(
-            CategoryRange::new(1, 2).intersect(&CategoryRange::new(3, 4)),
-            None
+            seed_to_soil.compose(&soil_to_fertilizer).to_string(),
+            "0 50 2\n54 52 46\n50 98 2\n"
         );
     }
 
     #[test]
     fn test_lookup_ranges() {
-        let map = CategoryMap{
-            entries: vec![
-                CategoryMapEntry{ source_range: CategoryRange::new(2, 2), dest_range_start: 10},
-                CategoryMapEntry{ source_range: CategoryRange::new(6, 4), dest_range_start: 20},
-            ]
-        };
+        let map = CategoryMap::from_entries(vec![
+            CategoryMapEntry{ source_range: CategoryRange::new(2, 2), dest_range_start: 10},
+            CategoryMapEntry{ source_range: CategoryRange::new(6, 4), dest_range_start: 20},
+        ]);
         assert_eq!(
             map.lookup_ranges(&[CategoryRange::new(2, 2), CategoryRange::new(4, 2), CategoryRange::new(6, 4)]),
             &[CategoryRange::new(10, 2), CategoryRange::new(4, 2), CategoryRange::new(20, 4)]
@@ -270,12 +538,10 @@ mod tests {
             map.lookup_ranges(&[CategoryRange::new(7, 5)]),
             &[CategoryRange::new(21, 3), CategoryRange::new(10, 2)]
         );
-        let adjacent_map = CategoryMap{
-            entries: vec![
-                CategoryMapEntry{ source_range: CategoryRange::new(2, 3), dest_range_start: 10},
-                CategoryMapEntry{ source_range: CategoryRange::new(5, 4), dest_range_start: 20},
-            ]
-        };
+        let adjacent_map = CategoryMap::from_entries(vec![
+            CategoryMapEntry{ source_range: CategoryRange::new(2, 3), dest_range_start: 10},
+            CategoryMapEntry{ source_range: CategoryRange::new(5, 4), dest_range_start: 20},
+        ]);
         assert_eq!(
             adjacent_map.lookup_ranges(&[CategoryRange::new(3, 5)]),
             &[CategoryRange::new(11, 2), CategoryRange::new(20, 3)]
@@ -287,5 +553,117 @@ mod tests {
         let input = include_str!("example.txt");
         verify!(part1, input, "35");
         verify!(part2, input, "46");
+        verify!(part2_reverse, input, "46");
+        verify!(min_location_seeds, input, "82..83");
+    }
+
+    #[test]
+    fn test_invert() {
+        let map = CategoryMap::from_entries(vec![
+            CategoryMapEntry{ source_range: CategoryRange::new(98, 2), dest_range_start: 50},
+            CategoryMapEntry{ source_range: CategoryRange::new(50, 48), dest_range_start: 52},
+        ]);
+        let inverse = map.invert().unwrap();
+        for value in 0..120 {
+            assert_eq!(inverse.lookup(map.lookup(value)), value);
+        }
+        let overlapping = CategoryMap::from_entries(vec![
+            CategoryMapEntry{ source_range: CategoryRange::new(10, 5), dest_range_start: 0},
+        ]);
+        assert!(overlapping.invert().is_none());
+        // 3..5 are reached both from 13..15 and from themselves
+        assert_eq!(
+            overlapping.reverse_lookup_range(&CategoryRange::new(3, 10)).intervals(),
+            &[Interval::new(3, 10), Interval::new(13, 15)]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let entry = |dest_range_start, source_start, length| {
+            CategoryMapEntry { source_range: CategoryRange::new(source_start, length), dest_range_start }
+        };
+        assert_eq!(CategoryMap::validate(&[entry(50, 98, 2), entry(52, 50, 48)]), vec![]);
+        assert_eq!(
+            CategoryMap::validate(&[
+                entry(0, 10, 5),
+                entry(100, 12, 5),
+                entry(3, 20, 5),
+                entry(7, 30, 0),
+                entry(i64::MAX - 1, 40, 2),
+            ]),
+            vec![
+                MapDiagnostic::ZeroLength(3),
+                MapDiagnostic::Overflow(4),
+                MapDiagnostic::OverlappingSources(0, 1),
+                MapDiagnostic::OverlappingDestinations(0, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chain() {
+        let input = include_str!("example.txt");
+        let (seeds, almanac) = parse_part1(input.lines()).unwrap();
+        let seed_to_humidity = almanac.map_between("seed", "humidity").unwrap();
+        let light_to_location = almanac.map_between("light", "location").unwrap();
+        let seed_to_light = almanac.map_between("seed", "light").unwrap();
+        // seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78 -> location 82
+        assert_eq!(seeds[0], 79);
+        assert_eq!(seed_to_humidity.lookup(79), 78);
+        assert_eq!(seed_to_light.lookup(79), 74);
+        assert_eq!(light_to_location.lookup(74), 82);
+        assert_eq!(almanac.chain("soil", "soil").unwrap().len(), 0);
+        assert_eq!(almanac.chain("water", "temperature").unwrap().len(), 2);
+        assert_eq!(almanac.chain("location", "seed").err(), Some(String::from("no chain of maps from location to seed")));
+    }
+
+    #[test]
+    fn invalid_map_order() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n\nwater-to-light map:\n88 18 7\n";
+        verify!(part1, input, Err("line 6, column 1: expected map from soil following the seed to soil map"));
+        let input = "seeds: 79 14\n\nseed to soil:\n50 98 2\n";
+        verify!(part1, input, Err("line 3, column 1: expected `<source>-to-<destination> map:`"));
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n";
+        verify!(part1, input, Err("no chain of maps from seed to location"));
+    }
+
+    #[test]
+    fn invalid_map() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 49\n";
+        verify!(part1, input, Err("line 5, column 1: expected source range not overlapping line 4"));
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 0\n52 50 49\n\nsoil-to-location map:\n1 2 3\n2 2 3\n";
+        verify!(part1, input, Err("line 4, column 1: expected range length greater than 0\n"));
+        verify!(part1, input, Err("line 9, column 1: expected source range not overlapping line 8"));
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 0\n";
+        verify!(part1, input, Err("line 4, column 1: expected range length greater than 0"));
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 9223372036854775800\n";
+        verify!(part1, input, Err("expected range ending at most 9223372036854775807"));
+    }
+
+    #[test]
+    fn overlapping_destinations() {
+        // 55 and 65 both map to 15, which is fine looking up but can't be inverted
+        let input = "seeds: 79 14 55 13\n\nseed-to-location map:\n10 50 10\n10 60 10\n";
+        verify!(part1, input, "13");
+        verify!(part2, input, "10");
+        verify!(part2_reverse, input, Err("not invertible"));
+    }
+
+    #[test]
+    fn empty_seed_ranges() {
+        let input = "seeds: 79 0\n\nseed-to-location map:\n10 50 10";
+        // part1 reads the same line as the seeds 79 and 0
+        verify!(part1, input, "0");
+        verify!(part2, input, Err("no seeds in any range"));
+        verify!(min_location_seeds, input, Err("no seeds in any range"));
+    }
+
+    #[test]
+    fn invalid_map_entry() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 x48\n";
+        verify!(part1, input, Err("line 5, column 1: expected `<destination start>"));
+        let input = "seeds: 79 1x4\n";
+        verify!(part1, input, Err("line 1, column 11: expected seed number"));
     }
 }
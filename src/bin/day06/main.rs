@@ -1,73 +1,110 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
-use std::str::Lines;
+use advent_of_code::{day, quadratic::Quadratic, Day, ParseError, Runner};
+use num::{integer::Average, Integer, Signed};
+use std::{process::ExitCode, str::Lines};
 
-fn parse_numbers(line: &str) -> Vec<u64> {
-    let numbers = line.split_once(':').unwrap().1;
-    numbers.split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect_vec()
+/// The numbers after `<label>:`, like `Time: 7 15 30`.
+fn numbers_part<'a>(line: &'a str, label: &str) -> Result<&'a str, ParseError> {
+    line.strip_prefix(label)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .ok_or_else(|| ParseError::expected(line, format!("`{}: <numbers>`", label)))
+}
+
+fn parse_numbers(line: &str, label: &str) -> Result<Vec<u64>, ParseError> {
+    numbers_part(line, label)?
+        .split_whitespace()
+        .map(|x| x.parse::<u64>().map_err(|_| ParseError::at(line, x, "number")))
+        .collect()
 }
 
 /**
  * The boat charges for hold_time, and then travels at velocity hold_time for
  * the remaining time (time - hold_time). To see if a run beats the record,
  * solve the inequality:
- * (time - hold_time) * hold_time >= record_distance + 1
- * 
- * Note record_distance + 1 since we must beat the record.
- * 
+ * (time - hold_time) * hold_time > record_distance
+ *
  * Rearrange:
- * -1 * hold_time^2 + time * hold_time - (record_distance + 1) > 0
+ * -1 * hold_time^2 + time * hold_time - record_distance > 0
  *
- * using the quadratic formula
- * (time +- sqrt(time^2 - 4*(record_distance+1))) / 2
+ * and count the hold times from 0 to time where that quadratic is positive,
+ * exactly with integers however large the numbers are.
  */
-fn count_ways_to_win(time: u64, record_distance: u64) -> usize {
-    let ftime = time as f64;
-    let discriminant = (ftime * ftime) - 4.0 * (record_distance + 1) as f64;
-    let sqrt_discriminant = discriminant.sqrt();
-    let hold_time_min = ((ftime - sqrt_discriminant) / 2.0).ceil() as u64;
-    let hold_time_max = ((ftime + sqrt_discriminant) / 2.0).floor() as u64;
-    (hold_time_max - hold_time_min + 1) as usize
+fn count_ways_to_win<T: Integer + Signed + Average + Clone>(time: T, record_distance: T) -> T {
+    Quadratic::new(-T::one(), time.clone(), -record_distance).count_positive(T::zero(), time)
 }
 
-fn part1(mut input: Lines) -> String {
-    let times = parse_numbers(input.next().expect("missing first line"));
-    let distances = parse_numbers(input.next().expect("missing second line"));
-    times.into_iter()
+fn part1(mut input: Lines) -> Result<i128, ParseError> {
+    let times = parse_numbers(input.next().unwrap_or_default(), "Time").map_err(|e| e.at_line(1))?;
+    let line = input.next().unwrap_or_default();
+    let distances = parse_numbers(line, "Distance").map_err(|e| e.at_line(2))?;
+    if distances.len() != times.len() {
+        let expected = format!("{} distances, one for each race", times.len());
+        return Err(ParseError::new(line, line.len()..line.len(), expected).at_line(2));
+    }
+    Ok(times.into_iter()
         .zip(distances)
-        .map(|(t, d)| count_ways_to_win(t, d))
-        .product::<usize>()
-        .to_string()
+        .map(|(t, d)| count_ways_to_win(t as i128, d as i128))
+        .product::<i128>())
 }
 
-fn parse_kerned_numbers(line: &str) -> u64 {
-    let numbers = line.split_once(':').unwrap().1;
-    numbers.replace(' ', "").parse::<u64>().unwrap()
+/// The numbers after `<label>:` read as one number, ignoring the spaces.
+fn parse_kerned_numbers(line: &str, label: &str) -> Result<u64, ParseError> {
+    let numbers = numbers_part(line, label)?;
+    numbers.replace(' ', "").parse::<u64>().map_err(|_| ParseError::at(line, numbers, "number"))
 }
 
-fn part2(mut input: Lines) -> String {
-    let time = parse_kerned_numbers(input.next().expect("missing first line"));
-    let distance = parse_kerned_numbers(input.next().expect("missing second line"));
-    count_ways_to_win(time, distance).to_string()
+fn part2(mut input: Lines) -> Result<i128, ParseError> {
+    let time = parse_kerned_numbers(input.next().unwrap_or_default(), "Time").map_err(|e| e.at_line(1))?;
+    let distance = parse_kerned_numbers(input.next().unwrap_or_default(), "Distance").map_err(|e| e.at_line(2))?;
+    Ok(count_ways_to_win(time as i128, distance as i128))
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use advent_of_code::verify;
+    use num::BigInt;
 
     #[test]
     fn test_count_ways_to_win() {
         assert_eq!(count_ways_to_win(7, 9), 4);
         assert_eq!(count_ways_to_win(15, 40), 8);
         assert_eq!(count_ways_to_win(30, 200), 9);
+        // the discriminant is a perfect square, so a root is exactly on the record
+        assert_eq!(count_ways_to_win(10, 24), 1);
+        assert_eq!(count_ways_to_win(10, 25), 0);
+        assert_eq!(count_ways_to_win(10, 21), 3);
+    }
+
+    #[test]
+    fn test_count_ways_to_win_brute_force() {
+        for time in 0i64..60 {
+            for record in 0..=time * time / 4 + 2 {
+                let expected = (0..=time).filter(|hold| hold * (time - hold) > record).count() as i64;
+                assert_eq!(count_ways_to_win(time, record), expected, "time={} record={}", time, record);
+                assert_eq!(count_ways_to_win(time as i128, record as i128), expected as i128);
+                assert_eq!(count_ways_to_win(BigInt::from(time), BigInt::from(record)), BigInt::from(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_ways_to_win_large() {
+        // beyond 2^53 where an f64 square root loses precision
+        let time = (1i128 << 60) + 7;
+        for record in [time * time / 4 - 1, time * time / 4 - (1 << 40), 12345] {
+            let ways = count_ways_to_win(time, record);
+            let longest = (time + ways - 1) / 2;
+            assert!(longest * (time - longest) > record);
+            assert!((longest + 1) * (time - longest - 1) <= record);
+            let big = count_ways_to_win(BigInt::from(time), BigInt::from(record));
+            assert_eq!(big, BigInt::from(ways));
+        }
     }
 
     #[test]
@@ -76,4 +113,15 @@ mod tests {
         verify!(part1, input, "288");
         verify!(part2, input, "71503");
     }
+
+    #[test]
+    fn invalid_races() {
+        let input = "Time: 7 x\nDistance: 9 40";
+        verify!(part1, input, Err("line 1, column 9: expected number"));
+        verify!(part2, input, Err("line 1, column 6: expected number"));
+        let input = "Time: 7 15\nDistance: 9";
+        verify!(part1, input, Err("line 2, column 12: expected 2 distances, one for each race"));
+        let input = "Time: 7";
+        verify!(part2, input, Err("line 2, column 1: expected `Distance: <numbers>`"));
+    }
 }
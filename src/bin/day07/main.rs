@@ -1,61 +1,5 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
-use std::{str::Lines, fmt::Debug};
-
-struct Rules {
-    card_values: &'static str,
-    wild: Option<u8>,
-}
-
-impl Rules {
-    fn parse_card(&self, card: char) -> u8 {
-        self.card_values.find(card).expect("bad card value") as u8
-    }
-
-    fn count_cards(&self, cards: &[u8]) -> [u8; 5] {
-        let mut sorted_cards = cards.to_vec();
-        sorted_cards.sort();
-        let mut counts = [0u8; 5];
-        let mut wild = 0;
-        for (num, card) in sorted_cards.into_iter().dedup_with_count() {
-            if self.wild.is_some_and(|w| w == card) {
-                wild = num
-            } else {
-                counts[counts.len()-num] += 1;
-            }
-        }
-
-        // if there is a wild, find the highest count and add the wilds to it
-        if wild > 0 {
-            if let Some((pos, count)) = counts.iter_mut().find_position(|count| count > &&mut 0u8) {
-                *count -= 1;
-                counts[pos - wild] += 1;
-            } else {
-                // handle all wilds case
-                counts[0] = 1;
-            }
-        }
-        counts
-    }
-
-    fn cards_str(&self, cards: &[u8]) -> String {
-        cards.iter().map(|&c| self.card_values.as_bytes()[c as usize] as char).collect::<String>()
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Hand {
-    counts: [u8; 5],
-    cards: Vec<u8>,
-}
-
-impl Hand {
-    fn new(hand: &str, rules: &Rules) -> Self {
-        let cards = hand.chars().map(|c| rules.parse_card(c)).collect_vec();
-        let counts = rules.count_cards(&cards);
-        Self { counts, cards }
-    }
-}
+use advent_of_code::{day, hand::{Hand, Rules}, log, Day, Named, Runner};
+use std::{str::Lines, error::Error, fmt::Debug, process::ExitCode};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct HandBid {
@@ -64,54 +8,77 @@ struct HandBid {
 }
 
 impl HandBid {
-    fn new(line: &str, rules: &Rules) -> Self {
-        let (hand, bid) = line.split_once(' ').expect("space");
-        Self {
-            hand: Hand::new(hand, rules),
-            bid: bid.parse::<usize>().expect("bid")
-        }
+    fn new(line: &str, rules: &Rules) -> Result<Self, Box<dyn Error>> {
+        let (hand, bid) = line.split_once(' ').ok_or("expected space between hand and bid")?;
+        Ok(Self {
+            hand: rules.parse_hand(hand)?,
+            bid: bid.parse::<usize>().map_err(|e| format!("bad bid {:?}: {}", bid, e))?
+        })
     }
 }
 
-const RULES_PART1: Rules = Rules {
-    card_values: "23456789TJQKA",
-    wild: None
-};
+const RULES_PART1: Named<Rules> = Named::create(Rules::CAMEL_CARDS, "part1");
+const RULES_PART2: Named<Rules> = Named::create(Rules::CAMEL_CARDS_JOKERS, "part2");
 
-
-fn part1(input: Lines) -> String {
-    let mut hands = input.map(|line| HandBid::new(line, &RULES_PART1)).collect_vec();
-    hands.sort();
-    hands.into_iter()
+/// Describes how each hand ranked under `rules`, including which card any
+/// jokers counted as, and its category under the `other` rules where that
+/// differs. With `changed_only`, only those hands are included.
+fn explain(hands: &[HandBid], rules: &Rules, other: &Named<Rules>, changed_only: bool) -> Result<Vec<String>, String> {
+    hands.iter()
         .enumerate()
-        .map(|(i, hb)| (i+1) * hb.bid)
-        .sum::<usize>()
-        .to_string()
+        .map(|(i, hb)| {
+            let cards = rules.cards_str(&hb.hand.cards);
+            let other_category = other.wrapped.parse_hand(&cards)
+                .map_err(|e| format!("cannot explain {} under {} rules: {}", cards, other.name, e))?
+                .category;
+            let changed = other_category != hb.hand.category;
+            if changed_only && !changed {
+                return Ok(None);
+            }
+            let mut line = format!("{} {:?}", cards, hb.hand.category);
+            if let Some(card) = hb.hand.wilds_as {
+                line += &format!(" (jokers as {})", rules.card_char(card));
+            }
+            if changed {
+                line += &format!(" ({}: {:?})", other.name, other_category);
+            }
+            let rank = i + 1;
+            Ok(Some(format!("{} rank={} bid={} winnings={}", line, rank, hb.bid, rank * hb.bid)))
+        })
+        .filter_map(Result::transpose)
+        .collect()
 }
 
-const RULES_PART2: Rules = Rules {
-    card_values: "J23456789TQKA",
-    wild: Some(0)
-};
-
-fn part2(input: Lines) -> String {
-    let mut hands = input.map(|line| HandBid::new(line, &RULES_PART2)).collect_vec();
+fn total_winnings(input: Lines, rules: &Rules, other: &Named<Rules>) -> Result<usize, Box<dyn Error>> {
+    let mut hands = input.map(|line| HandBid::new(line, rules)).collect::<Result<Vec<_>, _>>()?;
     hands.sort();
-    for hb in hands.iter() {
-        println!("hand={} cards={:?} counts={:?} bid={}", RULES_PART2.cards_str(&hb.hand.cards), hb.hand.cards, hb.hand.counts, hb.bid)
+    match advent_of_code::option("explain") {
+        None => {}
+        Some(filter @ ("" | "changed")) => {
+            for line in explain(&hands, rules, other, filter == "changed")? {
+                log!(Info, "{}", line);
+            }
+        }
+        Some(filter) => return Err(format!("unknown explain filter {:?}, expected --explain or --explain=changed", filter).into()),
     }
-    hands.into_iter()
+    Ok(hands.into_iter()
         .enumerate()
         .map(|(i, hb)| (i+1) * hb.bid)
-        .sum::<usize>()
-        .to_string()
+        .sum::<usize>())
+}
+
+fn part1(input: Lines) -> Result<usize, Box<dyn Error>> {
+    total_winnings(input, &RULES_PART1.wrapped, &RULES_PART2)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+fn part2(input: Lines) -> Result<usize, Box<dyn Error>> {
+    total_winnings(input, &RULES_PART2.wrapped, &RULES_PART1)
+}
+
+pub const DAY: Day = day!(part1, part2; options = ["explain"]);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -125,4 +92,34 @@ mod tests {
         verify!(part1, input, "6440");
         verify!(part2, input, "5905");
     }
+
+    #[test]
+    fn invalid_hands() {
+        let input = "32T3X 765";
+        verify!(part1, input, Err("bad card value 'X'"));
+        let input = "32T3K";
+        verify!(part1, input, Err("expected space"));
+        let input = "32T3K many";
+        verify!(part2, input, Err("bad bid \"many\""));
+    }
+
+    #[test]
+    fn explain_changed() {
+        let input = include_str!("example.txt");
+        let mut hands = input.lines().map(|line| HandBid::new(line, &RULES_PART2.wrapped).unwrap()).collect::<Vec<_>>();
+        hands.sort();
+        assert_eq!(explain(&hands, &RULES_PART2.wrapped, &RULES_PART1, true), Ok(vec![
+            "T55J5 FourOfAKind (jokers as 5) (part1: ThreeOfAKind) rank=3 bid=684 winnings=2052".to_string(),
+            "QQQJA FourOfAKind (jokers as Q) (part1: ThreeOfAKind) rank=4 bid=483 winnings=1932".to_string(),
+            "KTJJT FourOfAKind (jokers as T) (part1: TwoPair) rank=5 bid=220 winnings=1100".to_string(),
+        ]));
+        assert_eq!(explain(&hands, &RULES_PART2.wrapped, &RULES_PART1, false).unwrap()[0], "32T3K OnePair rank=1 bid=765 winnings=765");
+        let mut hands = input.lines().map(|line| HandBid::new(line, &RULES_PART1.wrapped).unwrap()).collect::<Vec<_>>();
+        hands.sort();
+        assert_eq!(explain(&hands, &RULES_PART1.wrapped, &RULES_PART2, true).unwrap()[0],
+            "KTJJT TwoPair (part2: FourOfAKind) rank=2 bid=220 winnings=440");
+        let six = Named::create(Rules { hand_size: 6, ..Rules::CAMEL_CARDS }, "six");
+        assert_eq!(explain(&hands, &RULES_PART1.wrapped, &six, true),
+            Err("cannot explain 32T3K under six rules: expected 6 cards in hand \"32T3K\"".to_string()));
+    }
 }
@@ -1,94 +1,152 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{day, graph::{self, Graph}, log, timed_parse, Day, ParseError, ParseLines, Runner};
 use itertools::Itertools;
-use num::integer::lcm;
-use std::str::Lines;
+use num::integer::{lcm, ExtendedGcd, Integer};
+use std::{collections::HashMap, error::Error, process::ExitCode, str::Lines};
 use lazy_regex::regex_captures;
 
-fn parse_step(step: char) -> bool {
+fn parse_step(step: char) -> Option<bool> {
     match step {
-        'L' => false,
-        'R' => true,
-        _ => panic!("unexpected step char {}", step)
+        'L' => Some(false),
+        'R' => Some(true),
+        _ => None
     }
 }
 
-fn parse_steps(line: &str) -> Vec<bool> {
-    line.chars().map(parse_step).collect_vec()
+fn parse_steps(line: &str) -> Result<Vec<bool>, ParseError> {
+    if line.is_empty() {
+        return Err(ParseError::new(line, 0..0, "`L` and `R` steps"));
+    }
+    line.char_indices()
+        .map(|(i, c)| parse_step(c).ok_or_else(|| ParseError::new(line, i..i + c.len_utf8(), "`L` or `R`")))
+        .collect()
 }
 
-const ID_CHARS: usize = 36;
+/// Index of a node name, assigned in order of first appearance.
+type NodeId = u32;
 
-fn parse_node_char(c: char) -> u16 {
-    c.to_digit(ID_CHARS as u32).expect("a-z") as u16
+/// Lookup from node names to ids. Names of up to three uppercase letters and
+/// digits, like every puzzle input, index a table directly instead of hashing.
+enum NameLookup {
+    Dense(Vec<Option<NodeId>>),
+    Hashed(HashMap<String, NodeId>),
 }
 
-fn parse_node_id(node_id: &str) -> u16 {
-    node_id.chars().fold(0u16, |out, c| {
-        out * ID_CHARS as u16 + parse_node_char(c)
+const DENSE_LEN: usize = 3;
+/// a digit per name char, plus zero for the missing chars of shorter names
+const DENSE_RADIX: usize = 37;
+
+fn dense_index(name: &str) -> Option<usize> {
+    if name.len() > DENSE_LEN {
+        return None;
+    }
+    name.chars().try_fold(0, |index, c| {
+        let digit = c.to_digit(36).filter(|_| !c.is_ascii_lowercase())?;
+        Some(index * DENSE_RADIX + digit as usize + 1)
     })
 }
 
-fn node_ends_with(node: u16, c: u16) -> bool {
-    node % ID_CHARS as u16 == c
+/// Interns node names so the network can be stored densely by [NodeId]
+/// whatever the names look like.
+struct Interner {
+    names: Vec<String>,
+    lookup: NameLookup,
 }
 
-fn format_node_id(node: u16) -> String {
-    let digit2 = node % ID_CHARS as u16;
-    let node = node / ID_CHARS as u16;
-    let digit1 = node % ID_CHARS as u16;
-    let node = node / ID_CHARS as u16;
-    let digit0 = node % ID_CHARS as u16;
-    [digit0, digit1, digit2]
-        .map(|d| std::char::from_digit(d as u32, ID_CHARS as u32).unwrap())
-        .map(|c| c.to_ascii_uppercase())
-        .into_iter()
-        .collect::<String>()
-    
-}
+impl Interner {
+    /// An interner for `names`, using the dense lookup if they all fit.
+    fn for_names<'a>(mut names: impl Iterator<Item = &'a str>) -> Self {
+        let lookup = if names.all(|name| dense_index(name).is_some()) {
+            NameLookup::Dense(vec![None; DENSE_RADIX.pow(DENSE_LEN as u32)])
+        } else {
+            NameLookup::Hashed(HashMap::new())
+        };
+        Self { names: Vec::new(), lookup }
+    }
+
+    fn get(&self, name: &str) -> Option<NodeId> {
+        match &self.lookup {
+            NameLookup::Dense(ids) => ids[dense_index(name)?],
+            NameLookup::Hashed(ids) => ids.get(name).copied(),
+        }
+    }
+
+    fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(id) = self.get(name) {
+            return id;
+        }
+        let id = self.names.len() as NodeId;
+        self.names.push(name.to_string());
+        match &mut self.lookup {
+            NameLookup::Dense(ids) => ids[dense_index(name).expect("checked all names fit")] = Some(id),
+            NameLookup::Hashed(ids) => {
+                ids.insert(name.to_string(), id);
+            }
+        }
+        id
+    }
 
-const NUM_IDS: usize = ID_CHARS * ID_CHARS * ID_CHARS;
+    fn name(&self, id: NodeId) -> &str {
+        &self.names[id as usize]
+    }
+}
 
 struct Network {
-    nodes: [Option<(u16, u16)>; NUM_IDS]
+    names: Interner,
+    /// left and right choices indexed by node id, `None` for nodes which
+    /// were only mentioned as a choice
+    nodes: Vec<Option<(NodeId, NodeId)>>,
 }
 
 impl Network {
-    fn parse_node(line: &str) -> (u16, (u16, u16)) {
-        let (_, node, left, right) = regex_captures!(r"([0-9A-Z]{3}) = \(([0-9A-Z]{3}), ([0-9A-Z]{3})\)", line).expect("match");
-        let node = parse_node_id(node);
-        let left = parse_node_id(left);
-        let right = parse_node_id(right);
-        (node, (left, right))
-    }
-
-    fn new(input: Lines) -> Self {
-        let mut nodes: [Option<(u16, u16)>; NUM_IDS] = [None; NUM_IDS];
-        for (node, dest) in input.map(Self::parse_node) {
-            nodes[node as usize] = Some(dest);
-        }
-        Self{ nodes }
+    fn parse_node(line: &str) -> Result<(&str, &str, &str, &str), ParseError> {
+        let (_, node, left, right) = regex_captures!(r"^([^\s=(),]+) = \(([^\s=(),]+), ([^\s=(),]+)\)$", line)
+            .ok_or_else(|| ParseError::expected(line, "`<node> = (<left>, <right>)`"))?;
+        Ok((line, node, left, right))
     }
 
-    fn start_nodes(&self, end: char) -> Vec<u16> {
-        let end = parse_node_char(end);
-        self.nodes.iter().enumerate().filter_map(|(i, n)| {
-            if n.is_some() && node_ends_with(i as u16, end) {
-                Some(i as u16)
-            } else {
-                None
+    /// Parses the node lines, which start on line 3 after the steps and a
+    /// blank line. Every choice must be a node with its own line.
+    fn new(input: Lines) -> Result<Self, ParseError> {
+        let lines = input.parse_lines_from(3, Self::parse_node).collect::<Result<Vec<_>, _>>()?;
+        let mut names = Interner::for_names(lines.iter().flat_map(|&(_, node, left, right)| [node, left, right]));
+        let mut nodes = Vec::new();
+        for &(_, node, left, right) in &lines {
+            let node = names.intern(node) as usize;
+            let dest = (names.intern(left), names.intern(right));
+            if nodes.len() < names.names.len() {
+                nodes.resize(names.names.len(), None);
             }
-        }).collect_vec()
+            nodes[node] = Some(dest);
+        }
+        for (n, &(line, _, left, right)) in lines.iter().enumerate() {
+            if let Some(&name) = [left, right].iter().find(|&&name| nodes[names.get(name).unwrap() as usize].is_none()) {
+                return Err(ParseError::at(line, name, "a node which has its own line").at_line(n + 3));
+            }
+        }
+        Ok(Self { names, nodes })
     }
 
-    fn choices(&self, node: u16) -> (u16, u16) {
-        if let Some(choices) = self.nodes[node as usize] {
-            choices
-        } else {
-            panic!("node {} does not exist", node)
-        }
+    fn id(&self, name: &str) -> Result<NodeId, String> {
+        self.names.get(name)
+            .filter(|&id| self.nodes[id as usize].is_some())
+            .ok_or_else(|| format!("node {} does not exist", name))
+    }
+
+    fn name(&self, node: NodeId) -> &str {
+        self.names.name(node)
+    }
+
+    fn start_nodes(&self, end: char) -> Vec<NodeId> {
+        (0..self.nodes.len() as NodeId)
+            .filter(|&node| self.nodes[node as usize].is_some() && self.name(node).ends_with(end))
+            .collect_vec()
+    }
+
+    fn choices(&self, node: NodeId) -> (NodeId, NodeId) {
+        self.nodes[node as usize].expect("parsing checked every choice has its own line")
     }
 
-    fn next(&self, node: u16, right: bool) -> u16 {
+    fn next(&self, node: NodeId, right: bool) -> NodeId {
         let choices = self.choices(node);
         match right {
             false => choices.0,
@@ -96,10 +154,10 @@ impl Network {
         }
     }
 
-    fn steps_to_end<'a, I, F>(&self, start_node: u16, steps: &mut I, is_end: F) -> (usize, u16)
+    fn steps_to_end<'a, I, F>(&self, start_node: NodeId, steps: &mut I, is_end: F) -> (usize, NodeId)
     where
         I: Iterator<Item = &'a bool>,
-        F: Fn(u16) -> bool,
+        F: Fn(NodeId) -> bool,
     {
         let mut node = start_node;
         let mut num_steps = 0usize;
@@ -109,48 +167,154 @@ impl Network {
         }
         (num_steps, node)
     }
+
+    /// The network with start nodes (ending in `A`) and end nodes (ending in
+    /// `Z`) highlighted and each edge labelled with the step taking it.
+    fn graph(&self) -> Graph {
+        let mut graph = Graph::new(true);
+        for node in 0..self.nodes.len() as NodeId {
+            let name = self.name(node);
+            let index = graph.add_node(name);
+            if name.ends_with('A') {
+                graph.highlight(index, "palegreen");
+            } else if name.ends_with('Z') {
+                graph.highlight(index, "lightcoral");
+            }
+        }
+        for (node, choices) in self.nodes.iter().enumerate() {
+            match *choices {
+                Some((left, right)) if left == right => graph.add_edge(node, left as usize, Some("L/R")),
+                Some((left, right)) => {
+                    graph.add_edge(node, left as usize, Some("L"));
+                    graph.add_edge(node, right as usize, Some("R"));
+                }
+                None => {}
+            }
+        }
+        graph
+    }
+}
+
+/// The steps on the first line, then the network after a blank line.
+fn parse_input(mut input: Lines) -> Result<(Vec<bool>, Network), ParseError> {
+    let steps = parse_steps(input.next().unwrap_or_default()).map_err(|e| e.at_line(1))?;
+    Ok((steps, Network::new(input.dropping(1))?))
 }
 
-fn part1(mut input: Lines) -> String {
-    let steps = parse_steps(input.next().expect("first line"));
-    let network = Network::new(input.dropping(1));
-    let start_node = parse_node_id("AAA");
-    let end_node = parse_node_id("ZZZ");
+fn part1(input: Lines) -> Result<usize, Box<dyn Error>> {
+    let (steps, network) = timed_parse(|| parse_input(input))?;
+    graph::export(|| network.graph())?;
+    let start_node = network.id("AAA")?;
+    let end_node = network.id("ZZZ")?;
     let is_end = |n| n == end_node;
-    network.steps_to_end(start_node, &mut steps.iter().cycle(), is_end).0.to_string()
-}
-
-fn part2(mut input: Lines) -> String {
-    let steps = parse_steps(input.next().expect("first line"));
-    let network = Network::new(input.dropping(1));
-    let start_nodes = network.start_nodes('A');
-    let end_node_digit = parse_node_char('Z');
-    let is_end = |n| node_ends_with(n, end_node_digit);
-
-    start_nodes.into_iter().map(|start_node| {
-        let mut node_steps = steps.iter().cycle();
-        // find first node ending in Z
-        let (end_steps, end_node ) = network.steps_to_end(start_node, &mut node_steps, is_end);
-        // continue until reaching another node ending in Z
-        let cycle_start_node = network.next(end_node, *node_steps.next().unwrap());
-        let (cycle_steps, cycle_node) = network.steps_to_end(cycle_start_node, &mut node_steps, is_end);
-        // logging shows some surprising things:
-        // 1. end_node == cycle_node
-        // 2. end_steps == cycle_steps + 1
-        println!("start_node={}({}) end_steps={} end_node={}({}) cycle_steps={} cycle_node={}({})",
-            start_node, format_node_id(start_node),
-            end_steps, end_node, format_node_id(end_node),
-            cycle_steps, cycle_node, format_node_id(cycle_node)
-        );
-        end_steps
-    }).reduce(lcm).unwrap().to_string()
-}
-
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+    Ok(network.steps_to_end(start_node, &mut steps.iter().cycle(), is_end).0)
+}
+
+/// When a ghost walking from one start node is on an end node. Its state is
+/// the node plus the position in the instructions, which must eventually
+/// repeat, so after `tail` steps the walk loops every `cycle_len` steps.
+#[derive(Debug, PartialEq, Eq)]
+struct Walk {
+    tail: i128,
+    cycle_len: i128,
+    /// steps before the cycle starts which land on an end node
+    tail_hits: Vec<i128>,
+    /// steps within the first pass of the cycle which land on an end node,
+    /// and which repeat every `cycle_len` steps
+    cycle_hits: Vec<i128>,
+}
+
+impl Walk {
+    fn new<F: Fn(NodeId) -> bool>(network: &Network, steps: &[bool], start_node: NodeId, is_end: F) -> Self {
+        let mut seen = HashMap::new();
+        let mut hits = Vec::new();
+        let mut node = start_node;
+        for step in 0.. {
+            let state = (node, step % steps.len());
+            if let Some(&tail) = seen.get(&state) {
+                let (tail_hits, cycle_hits) = hits.into_iter().partition(|&hit| hit < tail);
+                return Walk { tail, cycle_len: step as i128 - tail, tail_hits, cycle_hits };
+            }
+            seen.insert(state, step as i128);
+            if is_end(node) {
+                hits.push(step as i128);
+            }
+            node = network.next(node, steps[state.1]);
+        }
+        unreachable!()
+    }
+
+    fn is_hit(&self, step: i128) -> bool {
+        if step < self.tail {
+            self.tail_hits.contains(&step)
+        } else {
+            self.cycle_hits.iter().any(|hit| (step - hit).rem_euclid(self.cycle_len) == 0)
+        }
+    }
+
+    /// Whether the only end node is reached exactly once per cycle, on the
+    /// step which is a multiple of the cycle length, so that all walks first
+    /// line up at the LCM of their cycle lengths.
+    fn lcm_applies(&self) -> bool {
+        self.tail_hits.is_empty() && self.cycle_hits == [self.cycle_len]
+    }
+}
+
+/// Solves `x ≡ a1 (mod m1)` and `x ≡ a2 (mod m2)` for moduli which needn't be
+/// coprime, giving `x` modulo the LCM of the moduli if there is a solution.
+fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let ExtendedGcd { gcd, x, .. } = m1.extended_gcd(&m2);
+    if (a2 - a1) % gcd != 0 {
+        return None;
+    }
+    let modulus = m1 / gcd * m2;
+    let k = ((a2 - a1) / gcd * x).rem_euclid(m2 / gcd);
+    Some(((a1 + m1 * k).rem_euclid(modulus), modulus))
+}
+
+/// The first step on which every walk is on an end node, if any.
+fn first_common_hit(walks: &[Walk]) -> Option<i128> {
+    if walks.iter().all(Walk::lcm_applies) {
+        log!(Debug, "all walks hit an end node once per cycle, using the LCM of cycle lengths");
+        return walks.iter().map(|w| w.cycle_len).reduce(lcm);
+    }
+    // a common hit before every walk is cycling must be in the tail of the
+    // walk with the longest tail
+    let longest = walks.iter().max_by_key(|w| w.tail)?;
+    if let Some(&hit) = longest.tail_hits.iter().find(|&&hit| walks.iter().all(|w| w.is_hit(hit))) {
+        return Some(hit);
+    }
+    // otherwise combine every choice of cycle hit from each walk
+    let congruences = walks.iter().fold(vec![(0, 1)], |congruences, walk| {
+        congruences.into_iter()
+            .cartesian_product(walk.cycle_hits.iter())
+            .filter_map(|(c, &hit)| crt(c, (hit.rem_euclid(walk.cycle_len), walk.cycle_len)))
+            .collect_vec()
+    });
+    congruences.into_iter()
+        .map(|(residue, modulus)| residue + Integer::div_ceil(&(longest.tail - residue).max(0), &modulus) * modulus)
+        .min()
+}
+
+fn part2(input: Lines) -> Result<i128, Box<dyn Error>> {
+    let (steps, network) = timed_parse(|| parse_input(input))?;
+    graph::export(|| network.graph())?;
+    // whether each node is an end node, to avoid comparing names every step
+    let ends = (0..network.nodes.len() as NodeId).map(|n| network.name(n).ends_with('Z')).collect_vec();
+    let is_end = |n: NodeId| ends[n as usize];
+    let walks = network.start_nodes('A').into_iter().map(|start_node| {
+        let walk = Walk::new(&network, &steps, start_node, is_end);
+        log!(Debug, "start_node={} tail={} cycle_len={} tail_hits={:?} cycle_hits={:?}",
+            network.name(start_node), walk.tail, walk.cycle_len, walk.tail_hits, walk.cycle_hits);
+        walk
+    }).collect_vec();
+    Ok(first_common_hit(&walks).ok_or("ghosts never reach end nodes at the same time")?)
+}
+
+pub const DAY: Day = day!(part1, part2; options = ["graph"]);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -167,4 +331,67 @@ mod tests {
         let input3 = include_str!("example3.txt");
         verify!(part2, input3, "6");
     }
+
+    #[test]
+    fn node_names() {
+        let input = "R\n\nAAA = (B, ZZZ)\nB = (B, B)\nZZZ = (ZZZ, ZZZ)";
+        verify!(part1, input, "1");
+        let input = "LR\n\nghostA = (x, x)\nx = (ghostZ, ghostZ)\nghostZ = (ghostA, ghostA)\n\
+            other_A = (other_Z, other_Z)\nother_Z = (other_A, other_A)";
+        verify!(part2, input, "5");
+
+        let network = Network::new(include_str!("example3.txt").lines().dropping(2)).unwrap();
+        assert!(matches!(network.names.lookup, NameLookup::Dense(_)));
+        assert_eq!(network.name(network.id("22Z").unwrap()), "22Z");
+        let network = Network::new(["ghostA = (x, x)", "x = (ghostA, ghostA)"].join("\n").lines()).unwrap();
+        assert!(matches!(network.names.lookup, NameLookup::Hashed(_)));
+        assert_eq!(network.start_nodes('A').into_iter().map(|n| network.name(n)).collect_vec(), ["ghostA"]);
+    }
+
+    #[test]
+    fn test_graph() {
+        let network = Network::new(["AAA = (BBB, ZZZ)", "BBB = (AAA, AAA)", "ZZZ = (ZZZ, ZZZ)"].join("\n").lines()).unwrap();
+        assert_eq!(network.graph().to_mermaid(), "flowchart LR\n  n0[\"AAA\"]\n  n1[\"BBB\"]\n  n2[\"ZZZ\"]\n  \
+            n0 -->|\"L\"| n1\n  n0 -->|\"R\"| n2\n  n1 -->|\"L/R\"| n0\n  n2 -->|\"L/R\"| n2\n  \
+            style n0 fill:palegreen\n  style n2 fill:lightcoral\n");
+    }
+
+    #[test]
+    fn invalid_network() {
+        let input = "LR\n\nAAA = (BBB, ZZZ)\nBBB = bad\nZZZ = (ZZZ, ZZZ)";
+        verify!(part1, input, Err("line 4, column 1: expected `<node> = (<left>, <right>)`"));
+        let input = "LXR\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        verify!(part2, input, Err("line 1, column 2: expected `L` or `R`"));
+        let input = "\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        verify!(part1, input, Err("line 1, column 1: expected `L` and `R` steps"));
+        verify!(part2, input, Err("line 1, column 1: expected `L` and `R` steps"));
+        let input = "L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        verify!(part1, input, Err("line 3, column 8: expected a node which has its own line"));
+        let input = "L\n\nAAA = (AAA, AAA)";
+        verify!(part1, input, Err("node ZZZ does not exist"));
+    }
+
+    #[test]
+    fn offset_cycles() {
+        // 11A reaches 11Z every 2 steps after 2, 22A reaches 22Z every 3 steps after 1
+        let input = "L\n\n11A = (11B, XXX)\n11B = (11Z, XXX)\n11Z = (11B, XXX)\n\
+            22A = (22Z, XXX)\n22Z = (22B, XXX)\n22B = (22C, XXX)\n22C = (22Z, XXX)\nXXX = (XXX, XXX)";
+        verify!(part2, input, "4");
+        let input = "L\n\n11A = (11B, XXX)\n11B = (11Z, XXX)\n11Z = (11B, XXX)\n\
+            22A = (22Z, XXX)\n22Z = (22B, XXX)\n22B = (22Z, XXX)\nXXX = (XXX, XXX)";
+        verify!(part2, input, Err("never reach end nodes"));
+    }
+
+    #[test]
+    fn test_first_common_hit() {
+        assert_eq!(crt((2, 4), (3, 6)), None);
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        let tail = Walk { tail: 5, cycle_len: 4, tail_hits: vec![3], cycle_hits: vec![6] };
+        let every_third = Walk { tail: 0, cycle_len: 3, tail_hits: vec![], cycle_hits: vec![3] };
+        assert!(every_third.lcm_applies());
+        assert_eq!(first_common_hit(&[tail, every_third]), Some(3));
+        let late = Walk { tail: 7, cycle_len: 4, tail_hits: vec![], cycle_hits: vec![10] };
+        let even = Walk { tail: 0, cycle_len: 2, tail_hits: vec![], cycle_hits: vec![0] };
+        assert_eq!(first_common_hit(&[late, even]), Some(10));
+    }
 }
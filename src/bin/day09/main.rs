@@ -1,63 +1,36 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
-use std::str::Lines;
+use advent_of_code::{day, sequence::Polynomial, Day, ParseError, ParseLines, Runner};
+use num::BigInt;
+use std::{process::ExitCode, str::Lines};
 
-fn parse_value_history(line: &str) -> Vec<i64> {
-    line.split_whitespace().map(|x| x.parse::<i64>().expect("signed integer")).collect_vec()
-}
-
-fn compute_differences(values: &mut [i64]) {
-    if values.iter().any(|&v| v != 0) {
-        let last = values.len() - 1;
-        for i in 0..last {
-            values[i] = values[i+1] - values[i];
-        }
-        compute_differences(&mut values[0..last])    
+fn parse_value_history(line: &str) -> Result<Polynomial, ParseError> {
+    let values = line
+        .split_whitespace()
+        .map(|x| x.parse::<BigInt>().map_err(|_| ParseError::at(line, x, "signed integer")))
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.is_empty() {
+        return Err(ParseError::expected(line, "a history of signed integers"));
     }
+    Ok(Polynomial::fit(values))
 }
 
-fn extrapolate(mut values: Vec<i64>) -> i64
-{
-    compute_differences(&mut values);
-    values.into_iter().sum()
-}
-
-fn part1(input: Lines) -> String {
+fn part1(input: Lines) -> Result<BigInt, ParseError> {
     input
-        .map(parse_value_history)
-        .map(extrapolate)
-        .sum::<i64>()
-        .to_string()
-}
-
-fn compute_differences_prev(values: &mut [i64]) {
-    if values.iter().any(|&v| v != 0) {
-        for i in (1..values.len()).rev() {
-            values[i] -= values[i-1]
-        }
-        compute_differences_prev(&mut values[1..])    
-    }
-}
-
-fn extrapolate_prev(mut values: Vec<i64>) -> i64
-{
-    compute_differences_prev(&mut values);
-    values.into_iter().rev().fold(0, |acc, v| v - acc)
+        .parse_lines(parse_value_history)
+        .map(|history| history.map(|h| h.after(1)))
+        .sum()
 }
 
-fn part2(input: Lines) -> String {
+fn part2(input: Lines) -> Result<BigInt, ParseError> {
     input
-        .map(parse_value_history)
-        .map(extrapolate_prev)
-        .sum::<i64>()
-        .to_string()
+        .parse_lines(parse_value_history)
+        .map(|history| history.map(|h| h.before(1)))
+        .sum()
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -71,4 +44,22 @@ mod tests {
         verify!(part1, input, "114");
         verify!(part2, input, "2");
     }
+
+    #[test]
+    fn extrapolate_further() {
+        let history = parse_value_history("10 13 16 21 30 45").unwrap();
+        assert_eq!(history.degree(), Some(3));
+        assert_eq!(history.after(1), 68.into());
+        assert_eq!(history.after(2), 101.into());
+        assert_eq!(history.before(1), 5.into());
+        assert_eq!(history.before(2), (-4).into());
+    }
+
+    #[test]
+    fn invalid_history() {
+        let input = "0 3 6\n1 3 x 10";
+        verify!(part1, input, Err("line 2, column 5: expected signed integer"));
+        let input = "0 3 6\n\n1 3 6 10";
+        verify!(part2, input, Err("line 2, column 1: expected a history of signed integers"));
+    }
 }
@@ -1,26 +1,6 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{day, graph::{self, Graph}, grid::{Direction, Grid, Pos}, Day, ParseError, Runner};
 use itertools::Itertools;
-use std::{str::Lines, ops::Add};
-
-#[derive(PartialEq, Clone, Copy)]
-struct Pos {
-    row: isize,
-    col: isize,
-}
-
-impl Pos {
-    const fn new(row: isize, col: isize) -> Self {
-        Self { row, col }
-    }
-}
-
-impl Add for Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self{ row: self.row + rhs.row, col: self.col + rhs.col }
-    }
-}
+use std::{collections::HashSet, error::Error, str::Lines, process::ExitCode};
 
 #[derive(Clone, Copy)]
 struct Step {
@@ -28,36 +8,6 @@ struct Step {
     from: Direction,
 }
 
-#[derive(PartialEq, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    West,
-    East,
-}
-
-impl Direction {
-    const ALL: [Direction; 4] = [Self::North, Self::South, Self::East, Self::West];
-
-    fn clipped_step(self, pos: &Pos, size: &Pos) -> Option<Step> {
-        match self {
-            Self::North => if pos.row > 0 {Some(Pos::new(pos.row - 1, pos.col))} else {None},
-            Self::South => if pos.row+1 < size.row {Some(Pos::new(pos.row + 1, pos.col))} else {None},
-            Self::West => if pos.col > 0 {Some(Pos::new(pos.row, pos.col - 1))} else {None},
-            Self::East => if pos.col+1 < size.col {Some(Pos::new(pos.row, pos.col + 1))} else {None},
-        }.map(|pos| Step{ pos, from: self.flip() })
-    }
-
-    fn flip(self) -> Self {
-        match self {
-            Self::North => Self::South,
-            Self::South => Self::North,
-            Self::West => Self::East,
-            Self::East => Self::West,
-        }
-    }
-}
-
 #[derive(PartialEq, Clone, Copy)]
 enum Tile {
     NS,
@@ -71,17 +21,30 @@ enum Tile {
 }
 
 impl Tile {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '|' => Self::NS,
-            '-' => Self::EW,
-            'L' => Self::NE,
-            'J' => Self::NW,
-            '7' => Self::SW,
-            'F' => Self::SE,
-            '.' => Self::Ground,
-            'S' => Self::Start,
-            _ => panic!("no tile defined for character")
+            '|' => Some(Self::NS),
+            '-' => Some(Self::EW),
+            'L' => Some(Self::NE),
+            'J' => Some(Self::NW),
+            '7' => Some(Self::SW),
+            'F' => Some(Self::SE),
+            '.' => Some(Self::Ground),
+            'S' => Some(Self::Start),
+            _ => None
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Self::NS => '|',
+            Self::EW => '-',
+            Self::NE => 'L',
+            Self::NW => 'J',
+            Self::SW => '7',
+            Self::SE => 'F',
+            Self::Ground => '.',
+            Self::Start => 'S',
         }
     }
 
@@ -97,8 +60,15 @@ impl Tile {
         }
     }
 
+    /// The pipe connecting `a` and `b`, used to find what's under the start.
+    fn from_connections(a: Direction, b: Direction) -> Option<Self> {
+        [Tile::NS, Tile::EW, Tile::NE, Tile::NW, Tile::SW, Tile::SE]
+            .into_iter()
+            .find(|t| t.connects(a) && t.connects(b))
+    }
+
     fn connects(self, dir: Direction) -> bool {
-        self.connection_directions().iter().any(|&d| d == dir)
+        self.connection_directions().contains(&dir)
     }
 
     fn other(self, dir: Direction) -> Direction {
@@ -107,69 +77,134 @@ impl Tile {
 }
 
 struct Field {
-    tiles: Vec<Vec<Tile>>,
-    size: Pos,
+    tiles: Grid<Tile>,
 }
 
 impl Field {
-    fn from_lines(input: Lines) -> Self {
-        let tiles = input.map(|line| line.chars().map(Tile::from_char).collect_vec()).collect_vec();
-        let size = Pos::new(tiles.len() as isize, tiles[0].len() as isize);
-        Self { tiles, size }
+    fn from_lines(input: Lines) -> Result<Self, ParseError> {
+        let tiles = Grid::try_parse(input, "pipe tile", Tile::from_char)?;
+        Ok(Self { tiles })
     }
 
-    fn start_pos(&self) -> Pos {
-        self.tiles.iter().enumerate().find_map(|(row, tiles)| {
-            tiles.iter().find_position(|&&t| t == Tile::Start).map(|(col, _)| Pos::new(row as isize, col as isize))
-        }).expect("start position (S) must exist")
+    fn start_pos(&self) -> Option<Pos> {
+        self.tiles.find(|&t| t == Tile::Start)
     }
 
-    fn get_tile(&self, pos: &Pos) -> Tile {
-        self.tiles[pos.row as usize][pos.col as usize]
+    fn step(&self, pos: Pos, dir: Direction) -> Option<Step> {
+        self.tiles.step(pos, dir).map(|pos| Step{ pos, from: dir.flip() })
     }
 
-    fn start_steps(&self, start: &Pos) -> (Step, Step) {
-        let steps = Direction::ALL.iter()
-            .filter_map(|dir| {
-                dir.clipped_step(start, &self.size).filter(|step| self.get_tile(&step.pos).connects(dir.flip()))
+    /// Steps onto each neighbouring pipe which points back at the start.
+    fn start_steps(&self, start: Pos) -> Vec<Step> {
+        Direction::ALL.iter()
+            .filter_map(|&dir| {
+                self.step(start, dir).filter(|step| self.tiles[step.pos].connects(dir.flip()))
             })
-            .collect_vec();
-        assert_eq!(steps.len(), 2);
-        (steps[0], steps[1])
+            .collect_vec()
     }
 
-    fn next(&self, step: &Step) -> Step {
-        let tile = self.get_tile(&step.pos);
-        let dir = tile.other(step.from);
-        dir.clipped_step(&step.pos, &self.size).expect("in bounds")
+    /// Follows the pipe at `step` out the other side, or `None` if it doesn't
+    /// connect the way we came in or leads off the field.
+    fn next(&self, step: &Step) -> Option<Step> {
+        let tile = self.tiles[step.pos];
+        if !tile.connects(step.from) {
+            return None;
+        }
+        self.step(step.pos, tile.other(step.from))
     }
 
-    fn find_loop(&self) -> (usize, Pos) {
-        let start = self.start_pos();
-        let (a, b) = self.start_steps(&start);
-        let mut a = vec![a];
-        let mut b = vec![b];
-        while a.last().unwrap().pos != b.last().unwrap().pos {
-            a.push(self.next(a.last().unwrap()));
-            b.push(self.next(b.last().unwrap()));
+    /// Follows the pipes from `first` until they lead back to the start,
+    /// or `None` if they come to a dead end instead.
+    fn walk(&self, start: Pos, first: Step) -> Option<Loop> {
+        let mut path = vec![start];
+        let mut step = first;
+        while step.pos != start {
+            path.push(step.pos);
+            step = self.next(&step)?;
         }
-        (a.len(), a.last().unwrap().pos)
+        let start_tile = Tile::from_connections(first.from.flip(), step.from)?;
+        Some(Loop { path, start_tile })
+    }
+
+    /// Follows the pipes from the start all the way around back to it. More
+    /// than two pipes may point at the start, so it's the pair which closes
+    /// the loop, found by walking from each in turn.
+    fn find_loop(&self) -> Result<Loop, String> {
+        let start = self.start_pos().ok_or("no start position (S)")?;
+        self.start_steps(start)
+            .into_iter()
+            .find_map(|first| self.walk(start, first))
+            .ok_or_else(|| format!("no loop through the start at line {}, column {}", start.row + 1, start.col + 1))
+    }
+
+    /// Counts tiles inside the loop by scanning each row and tracking
+    /// whether we're inside. Crossing a loop tile which connects north flips
+    /// inside/outside, so a run like `L--7` crosses once while `L--J` turns
+    /// back and doesn't, which handles squeezing between adjacent pipes.
+    fn count_enclosed(&self, pipe_loop: &Loop) -> usize {
+        let on_loop = pipe_loop.path.iter().copied().collect::<HashSet<_>>();
+        let mut enclosed = 0;
+        for row in 0..self.tiles.rows() {
+            let mut inside = false;
+            for (col, &tile) in self.tiles.row(row).iter().enumerate() {
+                let pos = Pos::new(row, col);
+                if on_loop.contains(&pos) {
+                    let tile = if tile == Tile::Start { pipe_loop.start_tile } else { tile };
+                    if tile.connects(Direction::North) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    enclosed += 1;
+                }
+            }
+        }
+        enclosed
+    }
+
+    /// The loop as a ring of pipe tiles, placed where they are in the field
+    /// for layout engines like `neato`, with the start highlighted.
+    fn loop_graph(&self, pipe_loop: &Loop) -> Graph {
+        let mut graph = Graph::new(false);
+        for &pos in &pipe_loop.path {
+            let node = graph.add_node(self.tiles[pos].to_char().to_string());
+            graph.place(node, pos.col as f64, -(pos.row as f64));
+            if self.tiles[pos] == Tile::Start {
+                graph.highlight(node, "palegreen");
+            }
+        }
+        let len = pipe_loop.path.len();
+        for node in 0..len {
+            graph.add_edge(node, (node + 1) % len, None);
+        }
+        graph
     }
 }
 
-fn part1(input: Lines) -> String {
-    Field::from_lines(input).find_loop().0.to_string()
+struct Loop {
+    /// every tile on the loop in order, starting with the start tile
+    path: Vec<Pos>,
+    /// the pipe hidden under the start tile
+    start_tile: Tile,
 }
 
-fn part2(input: Lines) -> String {
-    input.take(0).count().to_string()
+fn part1(input: Lines) -> Result<usize, Box<dyn Error>> {
+    let field = Field::from_lines(input)?;
+    let pipe_loop = field.find_loop()?;
+    graph::export(|| field.loop_graph(&pipe_loop))?;
+    Ok(pipe_loop.path.len() / 2)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+fn part2(input: Lines) -> Result<usize, Box<dyn Error>> {
+    let field = Field::from_lines(input)?;
+    let pipe_loop = field.find_loop()?;
+    graph::export(|| field.loop_graph(&pipe_loop))?;
+    Ok(field.count_enclosed(&pipe_loop))
+}
+
+pub const DAY: Day = day!(part1, part2; options = ["graph"]);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
@@ -181,6 +216,54 @@ mod tests {
     fn example() {
         let input = include_str!("example.txt");
         verify!(part1, input, "8");
-        verify!(part2, input, "0");
+        verify!(part2, input, "1");
+    }
+
+    #[test]
+    fn example_enclosed() {
+        let input2 = include_str!("example2.txt");
+        verify!(part2, input2, "4");
+        let input3 = include_str!("example3.txt");
+        verify!(part2, input3, "4");
+        let input4 = include_str!("example4.txt");
+        verify!(part2, input4, "8");
+        let input5 = include_str!("example5.txt");
+        verify!(part2, input5, "10");
+    }
+
+    #[test]
+    fn test_start_tile() {
+        let input = include_str!("example.txt");
+        let field = Field::from_lines(input.lines()).unwrap();
+        let pipe_loop = field.find_loop().unwrap();
+        assert!(pipe_loop.start_tile == Tile::SE);
+        assert_eq!(pipe_loop.path.len(), 16);
+        assert_eq!(pipe_loop.path[0], Pos::new(2, 0));
+        let dot = field.loop_graph(&pipe_loop).to_dot();
+        assert!(dot.starts_with("graph {\n  n0 [label=\"S\", style=filled, fillcolor=palegreen, pos=\"0,-2!\"];\n"));
+        assert!(dot.ends_with("  n15 -- n0;\n}\n"));
+    }
+
+    #[test]
+    fn extra_pipe_at_start() {
+        let input = ".|...\n.S-7.\n.|.|.\n.L-J.";
+        verify!(part1, input, "4");
+        verify!(part2, input, "1");
+        let input = "-S-7\n.|.|\n.L-J";
+        verify!(part1, input, "4");
+    }
+
+    #[test]
+    fn no_loop() {
+        let input = ".S-.\n.|..";
+        verify!(part1, input, Err("no loop through the start at line 1, column 2"));
+        let input = "-7\nLJ";
+        verify!(part2, input, Err("no start position (S)"));
+    }
+
+    #[test]
+    fn invalid_tile() {
+        let input = "S-7\n|x|\nL-J";
+        verify!(part1, input, Err("line 2, column 2: expected pipe tile"));
     }
 }
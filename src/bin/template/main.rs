@@ -1,5 +1,5 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use std::str::Lines;
+use advent_of_code::{day, Day, Runner};
+use std::{process::ExitCode, str::Lines};
 
 fn part1(input: Lines) -> String {
     input.take(0).count().to_string()
@@ -9,11 +9,10 @@ fn part2(input: Lines) -> String {
     input.take(0).count().to_string()
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
-    runner.run(named!(part1), input);
-    runner.run(named!(part2), input);
+pub const DAY: Day = day!(part1, part2);
+
+fn main() -> ExitCode {
+    Runner::create().run(&[DAY])
 }
 
 #[cfg(test)]
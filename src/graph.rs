@@ -0,0 +1,229 @@
+//! Graphs of a day's structure written as Graphviz DOT or Mermaid flowcharts,
+//! for looking at a puzzle input rather than drawing it by hand.
+
+use crate::logging::LOG_TARGET;
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    Mermaid,
+}
+
+impl Format {
+    /// Mermaid for `.mmd` or `.mermaid` files, otherwise DOT.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("mmd" | "mermaid") => Self::Mermaid,
+            _ => Self::Dot,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    label: String,
+    /// fill color, any name both formats understand like `palegreen`
+    highlight: Option<&'static str>,
+    /// fixed position for DOT layout engines like `neato`, ignored by Mermaid
+    position: Option<(f64, f64)>,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Graph {
+    directed: bool,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+impl Graph {
+    pub fn new(directed: bool) -> Self {
+        Self { directed, nodes: Vec::new(), edges: Vec::new() }
+    }
+
+    /// Adds a node, returning the index used to refer to it.
+    pub fn add_node(&mut self, label: impl Into<String>) -> usize {
+        self.nodes.push(Node { label: label.into(), highlight: None, position: None });
+        self.nodes.len() - 1
+    }
+
+    pub fn highlight(&mut self, node: usize, color: &'static str) {
+        self.nodes[node].highlight = Some(color);
+    }
+
+    pub fn place(&mut self, node: usize, x: f64, y: f64) {
+        self.nodes[node].position = Some((x, y));
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, label: Option<&str>) {
+        self.edges.push(Edge { from, to, label: label.map(String::from) });
+    }
+
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        let mut out = format!("{} {{\n", keyword);
+        for (i, node) in self.nodes.iter().enumerate() {
+            let mut attrs = format!("label=\"{}\"", escape_dot(&node.label));
+            if let Some(color) = node.highlight {
+                write!(attrs, ", style=filled, fillcolor={}", color).unwrap();
+            }
+            if let Some((x, y)) = node.position {
+                write!(attrs, ", pos=\"{},{}!\"", x, y).unwrap();
+            }
+            writeln!(out, "  n{} [{}];", i, attrs).unwrap();
+        }
+        for edge in &self.edges {
+            write!(out, "  n{} {} n{}", edge.from, arrow, edge.to).unwrap();
+            if let Some(label) = &edge.label {
+                write!(out, " [label=\"{}\"]", escape_dot(label)).unwrap();
+            }
+            out += ";\n";
+        }
+        out += "}\n";
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let arrow = if self.directed { "-->" } else { "---" };
+        let mut out = String::from("flowchart LR\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            writeln!(out, "  n{}[\"{}\"]", i, escape_mermaid(&node.label)).unwrap();
+        }
+        for edge in &self.edges {
+            match &edge.label {
+                Some(label) => writeln!(out, "  n{} {}|\"{}\"| n{}", edge.from, arrow, escape_mermaid(label), edge.to),
+                None => writeln!(out, "  n{} {} n{}", edge.from, arrow, edge.to),
+            }
+            .unwrap();
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(color) = node.highlight {
+                writeln!(out, "  style n{} fill:{}", i, color).unwrap();
+            }
+        }
+        out
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Dot => self.to_dot(),
+            Format::Mermaid => self.to_mermaid(),
+        }
+    }
+}
+
+thread_local! {
+    /// Whether several days are running together, set by the [crate::Runner].
+    pub(crate) static SEVERAL_DAYS: Cell<bool> = const { Cell::new(false) };
+    /// The days which have written their graph already.
+    static EXPORTED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Adds the day to the file name, like `loop-day10.dot` for `loop.dot`, so
+/// days run together by `aoc` don't overwrite each other's graphs.
+fn day_path(path: &Path, day: Option<&str>) -> PathBuf {
+    let (Some(day), Some(stem)) = (day, path.file_stem()) else {
+        return path.to_path_buf();
+    };
+    let mut name = stem.to_os_string();
+    name.push(format!("-{}", day));
+    if let Some(ext) = path.extension() {
+        name.push(".");
+        name.push(ext);
+    }
+    path.with_file_name(name)
+}
+
+/// Writes the graph from `build` to the file given by `--graph=<path>`, as
+/// Mermaid for `.mmd` files and DOT otherwise. Does nothing without the
+/// option, so `build` only runs when the graph is wanted.
+///
+/// Each of a day's operations may export, and whichever runs first writes
+/// the graph. When `aoc` runs several days, the day is added to the file
+/// name so each writes its own.
+pub fn export<F: FnOnce() -> Graph>(build: F) -> Result<(), Box<dyn Error>> {
+    let Some(path) = crate::option("graph") else {
+        return Ok(());
+    };
+    if path.is_empty() {
+        return Err("--graph requires a path, like --graph=network.dot".into());
+    }
+    let day = LOG_TARGET.get().map(|(day, _)| day);
+    if let Some(day) = day {
+        if EXPORTED.with_borrow(|days| days.contains(&day)) {
+            return Ok(());
+        }
+        EXPORTED.with_borrow_mut(|days| days.push(day));
+    }
+    let path = day_path(Path::new(path), day.filter(|_| SEVERAL_DAYS.get()));
+    fs::write(&path, build().render(Format::from_path(&path)))
+        .map_err(|err| format!("cannot write graph {}: {}", path.display(), err))?;
+    crate::log!(Info, "wrote graph to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Graph {
+        let mut graph = Graph::new(true);
+        let a = graph.add_node("AAA");
+        let b = graph.add_node("say \"B\"");
+        graph.highlight(a, "palegreen");
+        graph.add_edge(a, b, Some("L"));
+        graph.add_edge(b, a, None);
+        graph
+    }
+
+    #[test]
+    fn test_dot() {
+        assert_eq!(
+            example().to_dot(),
+            "digraph {\n  n0 [label=\"AAA\", style=filled, fillcolor=palegreen];\n  n1 [label=\"say \\\"B\\\"\"];\n  \
+             n0 -> n1 [label=\"L\"];\n  n1 -> n0;\n}\n"
+        );
+        let mut graph = Graph::new(false);
+        let n = graph.add_node("|");
+        graph.place(n, 1.0, -2.0);
+        graph.add_edge(n, n, None);
+        assert_eq!(graph.to_dot(), "graph {\n  n0 [label=\"|\", pos=\"1,-2!\"];\n  n0 -- n0;\n}\n");
+    }
+
+    #[test]
+    fn test_mermaid() {
+        assert_eq!(
+            example().render(Format::from_path(Path::new("out.mmd"))),
+            "flowchart LR\n  n0[\"AAA\"]\n  n1[\"say #quot;B#quot;\"]\n  n0 -->|\"L\"| n1\n  n1 --> n0\n  style n0 fill:palegreen\n"
+        );
+        assert_eq!(Format::from_path(Path::new("out.dot")), Format::Dot);
+    }
+
+    #[test]
+    fn test_day_path() {
+        assert_eq!(day_path(Path::new("graphs/loop.dot"), Some("day10")), Path::new("graphs/loop-day10.dot"));
+        assert_eq!(day_path(Path::new("network"), Some("day08")), Path::new("network-day08"));
+        assert_eq!(day_path(Path::new("loop.dot"), None), Path::new("loop.dot"));
+    }
+}
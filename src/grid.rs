@@ -0,0 +1,229 @@
+//! A rectangular grid of cells parsed from lines of text, one cell per char.
+
+use crate::ParseError;
+use std::{
+    fmt,
+    ops::{Index, IndexMut, Range},
+    str::Lines,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::North, Self::South, Self::East, Self::West];
+
+    pub fn flip(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn parse<F: FnMut(char) -> T>(input: Lines, mut cell: F) -> Result<Self, ParseError> {
+        Self::try_parse(input, "", |c| Some(cell(c)))
+    }
+
+    /// Parses a grid where `cell` returns `None` for chars which aren't a
+    /// valid cell, reporting the position of the first such char.
+    pub fn try_parse<F>(input: Lines, expected: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = Vec::new();
+        let mut rows = 0;
+        let mut cols = None;
+        for (row, line) in input.enumerate() {
+            let row_start = cells.len();
+            for (i, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| ParseError::new(line, i..i + c.len_utf8(), expected).at_line(row + 1))?;
+                cells.push(value);
+            }
+            let row_cols = cells.len() - row_start;
+            if *cols.get_or_insert(row_cols) != row_cols {
+                let expected = format!("row of {} cells", cols.unwrap_or_default());
+                return Err(ParseError::expected(line, expected).at_line(row + 1));
+            }
+            rows += 1;
+        }
+        Ok(Self { cells, rows, cols: cols.unwrap_or_default() })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.rows && pos.col < self.cols
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.row * self.cols + pos.col])
+        } else {
+            None
+        }
+    }
+
+    /// Every position in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| Pos::new(row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell matching `predicate` in row-major order.
+    pub fn find<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<Pos> {
+        self.cells.iter().position(predicate).map(|i| Pos::new(i / self.cols, i % self.cols))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.cols.max(1)).take(self.rows)
+    }
+
+    /// Cells within `rows` and `cols`, clipped to the grid.
+    pub fn region(&self, rows: Range<usize>, cols: Range<usize>) -> impl Iterator<Item = (Pos, &T)> {
+        let rows = rows.start.min(self.rows)..rows.end.min(self.rows);
+        let cols = cols.start.min(self.cols)..cols.end.min(self.cols);
+        rows.flat_map(move |row| {
+            self.row(row)[cols.clone()]
+                .iter()
+                .zip(cols.clone())
+                .map(move |(cell, col)| (Pos::new(row, col), cell))
+        })
+    }
+
+    /// The neighbouring position in direction `dir`, or `None` at the edge.
+    pub fn step(&self, pos: Pos, dir: Direction) -> Option<Pos> {
+        match dir {
+            Direction::North => pos.row.checked_sub(1).map(|row| Pos::new(row, pos.col)),
+            Direction::South => Some(Pos::new(pos.row + 1, pos.col)),
+            Direction::West => pos.col.checked_sub(1).map(|col| Pos::new(pos.row, col)),
+            Direction::East => Some(Pos::new(pos.row, pos.col + 1)),
+        }
+        .filter(|&p| self.contains(p))
+    }
+
+    /// Orthogonally adjacent positions within the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL.into_iter().filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// Orthogonally and diagonally adjacent positions within the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.region(pos.row.saturating_sub(1)..pos.row + 2, pos.col.saturating_sub(1)..pos.col + 2)
+            .map(|(p, _)| p)
+            .filter(move |&p| p != pos)
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(f).collect(), rows: self.rows, cols: self.cols }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos).expect("position in grid")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        assert!(self.contains(pos), "position in grid");
+        &mut self.cells[pos.row * self.cols + pos.col]
+    }
+}
+
+/// Writes the grid back out as text, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            for cell in self.row(row) {
+                cell.fmt(f)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    const TEXT: &str = "abc\ndef\n";
+
+    #[test]
+    fn test_parse_display() {
+        let grid = Grid::parse(TEXT.lines(), |c| c).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[Pos::new(1, 2)], 'f');
+        assert_eq!(grid.to_string(), TEXT);
+        let error = Grid::parse("abc\nde".lines(), |c| c).unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (Some(2), "row of 3 cells"));
+        let error = Grid::try_parse("ab\na?".lines(), "letter", |c| Some(c).filter(char::is_ascii_alphabetic)).unwrap_err();
+        assert_eq!((error.line, error.span), (Some(2), 1..2));
+    }
+
+    #[test]
+    fn test_slicing() {
+        let grid = Grid::parse(TEXT.lines(), |c| c).unwrap();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.region(1..5, 1..5).map(|(_, &c)| c).collect::<String>(), "ef");
+        assert_eq!(grid.find(|&c| c == 'e'), Some(Pos::new(1, 1)));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::parse(TEXT.lines(), |c| c).unwrap();
+        let corner = Pos::new(0, 0);
+        assert_eq!(grid.neighbors4(corner).map(|p| grid[p]).sorted().collect::<String>(), "bd");
+        assert_eq!(grid.neighbors8(corner).map(|p| grid[p]).collect::<String>(), "bde");
+        assert_eq!(grid.neighbors8(Pos::new(1, 1)).map(|p| grid[p]).collect::<String>(), "abcdf");
+        assert_eq!(grid.step(Pos::new(1, 2), Direction::East), None);
+        assert_eq!(grid.step(Pos::new(1, 2), Direction::North), Some(Pos::new(0, 2)));
+    }
+}
@@ -0,0 +1,194 @@
+//! Ranks hands of cards by category, such as a full house, then by a
+//! tiebreak, with optional wild cards which count as whatever rank makes the
+//! best hand.
+
+use itertools::Itertools;
+use std::cmp::Reverse;
+
+/// Hand categories from worst to best, determined by the sizes of the two
+/// largest groups of matching cards so that they apply to any hand size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl Category {
+    fn from_groups(largest: usize, second: usize) -> Self {
+        match (largest, second) {
+            (5.., _) => Self::FiveOfAKind,
+            (4, _) => Self::FourOfAKind,
+            (3, 2..) => Self::FullHouse,
+            (3, _) => Self::ThreeOfAKind,
+            (2, 2..) => Self::TwoPair,
+            (2, _) => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
+/// How hands of the same category are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tiebreak {
+    /// compare card values in the order the cards were dealt
+    InOrder,
+    /// compare card values from the largest group to the smallest, and from
+    /// highest to lowest value within groups of the same size, like poker
+    Grouped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// card ranks from lowest to highest value
+    pub card_values: &'static str,
+    /// ranks which count as any other rank when categorizing a hand
+    pub wild: &'static str,
+    pub hand_size: usize,
+    pub tiebreak: Tiebreak,
+}
+
+/// A hand evaluated by a set of [Rules], where hands compare by category and
+/// then tiebreak.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hand {
+    pub category: Category,
+    tiebreak: Vec<u8>,
+    /// card values, indexing into [Rules::card_values]
+    pub cards: Vec<u8>,
+    /// value every wild card counts as to reach the category, if any are wild
+    pub wilds_as: Option<u8>,
+}
+
+impl Rules {
+    /// Camel Cards from 2023 day 7 part 1.
+    pub const CAMEL_CARDS: Rules = Rules {
+        card_values: "23456789TJQKA",
+        wild: "",
+        hand_size: 5,
+        tiebreak: Tiebreak::InOrder,
+    };
+
+    /// Camel Cards from 2023 day 7 part 2, where `J` is a joker: wild, and
+    /// the lowest value for tiebreaks.
+    pub const CAMEL_CARDS_JOKERS: Rules = Rules {
+        card_values: "J23456789TQKA",
+        wild: "J",
+        ..Self::CAMEL_CARDS
+    };
+
+    pub fn parse_card(&self, card: char) -> Result<u8, String> {
+        self.card_values
+            .find(card)
+            .map(|value| value as u8)
+            .ok_or_else(|| format!("bad card value {:?}", card))
+    }
+
+    pub fn card_char(&self, card: u8) -> char {
+        self.card_values.as_bytes()[card as usize] as char
+    }
+
+    pub fn cards_str(&self, cards: &[u8]) -> String {
+        cards.iter().map(|&c| self.card_char(c)).collect()
+    }
+
+    fn is_wild(&self, card: u8) -> bool {
+        self.wild.contains(self.card_char(card))
+    }
+
+    pub fn parse_hand(&self, hand: &str) -> Result<Hand, String> {
+        let cards = hand.chars().map(|c| self.parse_card(c)).collect::<Result<Vec<_>, _>>()?;
+        if cards.len() != self.hand_size {
+            return Err(format!("expected {} cards in hand {:?}", self.hand_size, hand));
+        }
+        Ok(self.evaluate(cards))
+    }
+
+    pub fn evaluate(&self, cards: Vec<u8>) -> Hand {
+        let (wild, natural): (Vec<u8>, Vec<u8>) = cards.iter().partition(|&&c| self.is_wild(c));
+        // groups of matching natural cards, largest and then highest first
+        let groups = natural
+            .iter()
+            .sorted()
+            .dedup_with_count()
+            .map(|(count, &card)| (count, card))
+            .sorted_by_key(|&group| Reverse(group))
+            .collect_vec();
+        // adding every wild card to the largest group always gives the best category
+        let largest = groups.first().map_or(0, |g| g.0) + wild.len();
+        let second = groups.get(1).map_or(0, |g| g.0);
+        let wilds_as = if wild.is_empty() {
+            None
+        } else {
+            // when every card is wild, count them as the highest value
+            Some(groups.first().map_or(self.card_values.len() as u8 - 1, |g| g.1))
+        };
+        let tiebreak = match self.tiebreak {
+            Tiebreak::InOrder => cards.clone(),
+            Tiebreak::Grouped => {
+                let wild_groups = wild.iter().sorted_by_key(|&&c| Reverse(c)).map(|&c| (1, c));
+                groups
+                    .iter()
+                    .copied()
+                    .chain(wild_groups)
+                    .sorted_by_key(|&group| Reverse(group))
+                    .flat_map(|(count, card)| std::iter::repeat_n(card, count))
+                    .collect_vec()
+            }
+        };
+        Hand { category: Category::from_groups(largest, second), tiebreak, cards, wilds_as }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(rules: &Rules, hand: &str) -> Category {
+        rules.parse_hand(hand).unwrap().category
+    }
+
+    #[test]
+    fn test_categories() {
+        let rules = Rules::CAMEL_CARDS;
+        assert_eq!(category(&rules, "AAAAA"), Category::FiveOfAKind);
+        assert_eq!(category(&rules, "AA8AA"), Category::FourOfAKind);
+        assert_eq!(category(&rules, "23332"), Category::FullHouse);
+        assert_eq!(category(&rules, "TTT98"), Category::ThreeOfAKind);
+        assert_eq!(category(&rules, "23432"), Category::TwoPair);
+        assert_eq!(category(&rules, "A23A4"), Category::OnePair);
+        assert_eq!(category(&rules, "23456"), Category::HighCard);
+        assert_eq!(category(&rules, "KTJJT"), Category::TwoPair);
+        assert!(rules.parse_hand("2345").is_err());
+    }
+
+    #[test]
+    fn test_wild() {
+        let rules = Rules::CAMEL_CARDS_JOKERS;
+        assert_eq!(category(&rules, "KTJJT"), Category::FourOfAKind);
+        assert_eq!(category(&rules, "JJJJJ"), Category::FiveOfAKind);
+        assert_eq!(category(&rules, "QJJQ2"), Category::FourOfAKind);
+        assert_eq!(category(&rules, "2345J"), Category::OnePair);
+        assert_eq!(rules.parse_hand("T55J5").unwrap().wilds_as, rules.parse_card('5').ok());
+        assert!(rules.parse_hand("JKKK2") < rules.parse_hand("QQQQ2"));
+
+        let two_wild = Rules { wild: "J2", ..Rules::CAMEL_CARDS_JOKERS };
+        assert_eq!(category(&two_wild, "2J3K4"), Category::ThreeOfAKind);
+        assert_eq!(category(&two_wild, "22JJA"), Category::FiveOfAKind);
+    }
+
+    #[test]
+    fn test_hand_size_and_tiebreak() {
+        let rules = Rules { hand_size: 7, tiebreak: Tiebreak::Grouped, ..Rules::CAMEL_CARDS };
+        assert_eq!(category(&rules, "2233344"), Category::FullHouse);
+        assert_eq!(category(&rules, "AAAAAA2"), Category::FiveOfAKind);
+        // grouped compares the pair of 5s before the ace
+        assert!(rules.parse_hand("A553297") < rules.parse_hand("2663497"));
+        let in_order = Rules { hand_size: 7, ..Rules::CAMEL_CARDS };
+        assert!(in_order.parse_hand("A553297") > in_order.parse_hand("2663497"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Sets of integer intervals, and maps which shift values by a different
+//! offset within each interval, as used by day05's almanac.
+
+use num::{PrimInt, Signed};
+
+/// The half-open interval `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn with_len(start: T, len: T) -> Self {
+        Self { start, end: start + len }
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        value >= self.start && value < self.end
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        Some(Self::new(self.start.max(other.start), self.end.min(other.end))).filter(|i| !i.is_empty())
+    }
+
+    fn shift(self, offset: T) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+/// A set of integers stored as sorted intervals, which are merged whenever
+/// they overlap or are adjacent so that each set has one representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: Vec::new() }
+    }
+
+    fn normalize(mut intervals: Vec<Interval<T>>) -> Vec<Interval<T>> {
+        intervals.retain(|i| !i.is_empty());
+        intervals.sort_by_key(|i| i.start);
+        intervals.into_iter().fold(Vec::new(), |mut merged: Vec<Interval<T>>, i| {
+            match merged.last_mut() {
+                Some(last) if i.start <= last.end => last.end = last.end.max(i.end),
+                _ => merged.push(i),
+            }
+            merged
+        })
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        self.intervals.push(interval);
+        self.intervals = Self::normalize(std::mem::take(&mut self.intervals));
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(i).is_some_and(|i| i.contains(value))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    /// Number of integers in the set.
+    pub fn count(&self) -> T {
+        self.intervals.iter().fold(T::zero(), |sum, i| sum + i.len())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals.iter().chain(other.intervals.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut a, mut b) = (self.intervals.iter().peekable(), other.intervals.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            result.extend(x.intersect(y));
+            // advance whichever ends first, since it can't intersect anything else
+            if x.end <= y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { intervals: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        for &interval in self.intervals.iter() {
+            let remaining = other
+                .intervals
+                .iter()
+                .filter_map(|o| o.intersect(&interval))
+                .fold(interval, |remaining, cut| {
+                    result.push(Interval::new(remaining.start, cut.start));
+                    Interval::new(cut.end, remaining.end)
+                });
+            result.push(remaining);
+        }
+        Self::from_iter(result)
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self { intervals: Self::normalize(iter.into_iter().collect()) }
+    }
+}
+
+/// Maps each value by adding the offset of the piece containing it, leaving
+/// values outside every piece unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseShift<T> {
+    /// sorted, non-overlapping source intervals and their offsets
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: PrimInt + Signed> PiecewiseShift<T> {
+    /// Where pieces overlap, the one starting first takes precedence.
+    pub fn new<I: IntoIterator<Item = (Interval<T>, T)>>(pieces: I) -> Self {
+        let mut sorted = pieces.into_iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|(i, _)| i.start);
+        let mut pieces: Vec<(Interval<T>, T)> = Vec::with_capacity(sorted.len());
+        for (interval, offset) in sorted {
+            let start = pieces.last().map_or(interval.start, |(last, _)| interval.start.max(last.end));
+            let interval = Interval::new(start, interval.end);
+            if !interval.is_empty() {
+                pieces.push((interval, offset));
+            }
+        }
+        Self { pieces }
+    }
+
+    pub fn identity() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    pub fn pieces(&self) -> &[(Interval<T>, T)] {
+        &self.pieces
+    }
+
+    pub fn apply(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|(i, _)| i.end <= value);
+        match self.pieces.get(i) {
+            Some((interval, offset)) if interval.contains(value) => value + *offset,
+            _ => value,
+        }
+    }
+
+    /// Splits `interval` into consecutive parts, each paired with the offset
+    /// applied to it, which is zero in the gaps between pieces.
+    pub fn split(&self, interval: Interval<T>) -> Vec<(Interval<T>, T)> {
+        let mut parts = Vec::new();
+        let mut start = interval.start;
+        for (piece, offset) in self.pieces.iter() {
+            if let Some(part) = piece.intersect(&interval) {
+                if start < part.start {
+                    parts.push((Interval::new(start, part.start), T::zero()));
+                }
+                parts.push((part, *offset));
+                start = part.end;
+            }
+        }
+        if start < interval.end {
+            parts.push((Interval::new(start, interval.end), T::zero()));
+        }
+        parts
+    }
+
+    /// Images of the parts of `interval`, in the order of the parts.
+    pub fn apply_interval(&self, interval: Interval<T>) -> Vec<Interval<T>> {
+        self.split(interval).into_iter().map(|(part, offset)| part.shift(offset)).collect()
+    }
+
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.intervals().iter().flat_map(|&i| self.apply_interval(i)).collect()
+    }
+
+    /// The single map equivalent to applying `self` and then `then`.
+    pub fn compose(&self, then: &Self) -> Self {
+        let everything = Interval::new(T::min_value(), T::max_value());
+        let mut pieces: Vec<(Interval<T>, T)> = Vec::new();
+        for (part, offset) in self.split(everything) {
+            for (image, then_offset) in then.split(part.shift(offset)) {
+                let source = image.shift(-offset);
+                let total = offset + then_offset;
+                match pieces.last_mut() {
+                    Some((last, last_offset)) if last.end == source.start && *last_offset == total => {
+                        last.end = source.end
+                    }
+                    _ => pieces.push((source, total)),
+                }
+            }
+        }
+        pieces.retain(|(_, offset)| !offset.is_zero());
+        Self { pieces }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    #[test]
+    fn test_intersect() {
+        let with_len = Interval::with_len;
+        assert_eq!(with_len(1, 5).intersect(&with_len(2, 3)), Some(with_len(2, 3)));
+        assert_eq!(with_len(2, 3).intersect(&with_len(1, 5)), Some(with_len(2, 3)));
+        assert_eq!(with_len(1, 3).intersect(&with_len(2, 5)), Some(with_len(2, 2)));
+        assert_eq!(with_len(2, 5).intersect(&with_len(1, 3)), Some(with_len(2, 2)));
+        assert_eq!(with_len(1, 2).intersect(&with_len(3, 4)), None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(set(&[(5, 7), (1, 3), (3, 4), (6, 9), (10, 10)]).intervals(), set(&[(1, 4), (5, 9)]).intervals());
+        assert_eq!(set(&[(1, 4), (5, 9)]).count(), 7);
+        assert!(set(&[(1, 4)]).contains(3));
+        assert!(!set(&[(1, 4)]).contains(4));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 30)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20)]));
+        assert_eq!(a.difference(&set(&[(2, 3), (4, 6), (28, 40)])), set(&[(0, 2), (3, 4), (6, 10), (20, 28)]));
+    }
+
+    #[test]
+    fn test_apply() {
+        let shift = PiecewiseShift::new([(Interval::with_len(6, 4), 14), (Interval::with_len(2, 2), 8)]);
+        assert_eq!([1, 2, 3, 4, 9, 10].map(|v| shift.apply(v)), [1, 10, 11, 4, 23, 10]);
+        assert_eq!(
+            shift.apply_interval(Interval::with_len(1, 10)),
+            [(1, 2), (10, 12), (4, 6), (20, 24), (10, 11)].map(|(s, e)| Interval::new(s, e))
+        );
+        assert_eq!(shift.apply_set(&set(&[(1, 11)])), set(&[(1, 2), (4, 6), (10, 12), (20, 24)]));
+    }
+
+    #[test]
+    fn test_compose() {
+        let a = PiecewiseShift::new([(Interval::new(0, 10), 5), (Interval::new(20, 30), -20)]);
+        let b = PiecewiseShift::new([(Interval::new(5, 8), 100), (Interval::new(12, 15), -12)]);
+        let composed = a.compose(&b);
+        for v in -5..40 {
+            assert_eq!(composed.apply(v), b.apply(a.apply(v)), "value {}", v);
+        }
+        assert_eq!(composed.compose(&PiecewiseShift::identity()), composed);
+        // shifting forward then back again cancels out, leaving only values
+        // which were only shifted back
+        let forward = PiecewiseShift::new([(Interval::new(0, 10), 5)]);
+        let back = PiecewiseShift::new([(Interval::new(5, 15), -5)]);
+        assert_eq!(forward.compose(&back).pieces(), &[(Interval::new(10, 15), -5)]);
+    }
+}
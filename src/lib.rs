@@ -1,4 +1,31 @@
-use std::{collections::HashSet, str::Lines};
+mod answers;
+pub mod graph;
+pub mod grid;
+pub mod hand;
+pub mod interval;
+mod logging;
+mod parse;
+pub mod quadratic;
+pub mod scanner;
+pub mod sequence;
+
+use answers::{Answers, Check};
+use logging::{LogConfig, LOG_CONFIG, LOG_TARGET};
+pub use logging::{log_enabled, log_message, Level};
+pub use parse::{ParseError, ParseErrors, ParseLines};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt, fs,
+    io::{self, Read},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::Lines,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
 
 pub struct Named<T> {
     pub wrapped: T,
@@ -6,61 +33,594 @@ pub struct Named<T> {
 }
 
 impl<T> Named<T> {
-    pub fn create(t: T, name: &'static str) -> Self {
+    pub const fn create(t: T, name: &'static str) -> Self {
         Self { wrapped: t, name }
     }
 }
 
+/// An operation's answer, or why it couldn't produce one.
+pub type Answer = Result<String, Box<dyn Error>>;
+
+pub type Operation = fn(Lines) -> Answer;
+
+/// Converts what an operation returns into an [Answer], so operations may
+/// return either a `String` or a `Result` of anything displayable.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Answer;
+}
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Answer {
+        Ok(self)
+    }
+}
+
+impl<T: fmt::Display, E: Into<Box<dyn Error>>> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Answer {
+        self.map(|answer| answer.to_string()).map_err(Into::into)
+    }
+}
+
+/// The operations making up one day's solution, so that they can be run
+/// either by the day's own binary or by the `aoc` binary running every day.
+pub struct Day {
+    module_path: &'static str,
+    pub operations: &'static [Named<Operation>],
+    /// names of the `--name=value` options the day reads with [option]
+    pub options: &'static [&'static str],
+}
+
+impl Day {
+    pub const fn new(module_path: &'static str, operations: &'static [Named<Operation>]) -> Self {
+        Self { module_path, operations, options: &[] }
+    }
+
+    pub const fn with_options(self, options: &'static [&'static str]) -> Self {
+        Self { options, ..self }
+    }
+
+    /// Name of the directory under `src/bin` containing the day, e.g. `day01`.
+    pub fn name(&self) -> &'static str {
+        self.module_path.rsplit("::").next().unwrap_or(self.module_path)
+    }
+
+    pub fn number(&self) -> Option<u32> {
+        self.name().strip_prefix("day").and_then(|n| n.parse().ok())
+    }
+}
+
+/// Declares a [Day] from the module it's used in, e.g. `day!(part1, part2)`,
+/// or `day!(part1, part2; options = ["explain"])` for a day reading options.
 #[macro_export]
-macro_rules! named {
-    ($x:ident) => {
-        &Named::create($x, std::stringify!($x))
+macro_rules! day {
+    ( $($op:ident),* $(,)? ) => {
+        $crate::day!($($op),*; options = [])
+    };
+    ( $($op:ident),* ; options = [$($option:literal),* $(,)?] ) => {
+        $crate::Day::new(module_path!(), &[$($crate::Named::create(
+            |input| $crate::IntoAnswer::into_answer($op(input)),
+            std::stringify!($op),
+        )),*])
+        .with_options(&[$($option),*])
     };
 }
 
-pub struct Runner {
-    module_name: &'static str,
+/// Where the puzzle input for a run comes from.
+#[derive(Debug, PartialEq, Eq)]
+enum InputSource {
+    /// `src/bin/<day>/input.txt` in the crate.
+    Default,
+    /// `--input <path>`
+    File(PathBuf),
+    /// `--input -` or a bare `-`
+    Stdin,
+}
+
+impl InputSource {
+    fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            Self::Stdin
+        } else {
+            Self::File(PathBuf::from(arg))
+        }
+    }
+}
+
+#[derive(Debug)]
+enum InputError {
+    MissingValue(String),
+    InvalidLog(String),
+    /// a custom input was given with this many days selected, rather than one
+    InputForDays(usize),
+    /// an operation name which none of the selected days have, and the names they do have
+    UnknownOperation(String, Vec<&'static str>),
+    /// an option which none of the selected days read, and the ones they do
+    UnknownOption(String, Vec<&'static str>),
+    Read { path: PathBuf, default: bool, err: io::Error },
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingValue(flag) => write!(f, "{} requires a path (or - for stdin)", flag),
+            Self::InputForDays(days) => write!(
+                f,
+                "--input and - give one day's input, but {} days are selected; select a single day like `5 -`",
+                days
+            ),
+            Self::UnknownOperation(name, known) => write!(
+                f,
+                "unknown operation {:?}, expected one of {} (day options are given as --name=value)",
+                name,
+                known.join(", ")
+            ),
+            Self::UnknownOption(name, known) if known.is_empty() => {
+                write!(f, "unknown option --{}, the selected days don't read any options", name)
+            }
+            Self::UnknownOption(name, known) => write!(
+                f,
+                "unknown option --{}, expected one of {}",
+                name,
+                known.iter().map(|known| format!("--{}", known)).collect::<Vec<_>>().join(", ")
+            ),
+            Self::InvalidLog(spec) => write!(
+                f,
+                "invalid --log={}, expected a level or target=level list like debug,day08/part2=trace",
+                spec
+            ),
+            Self::Read { path, default, err } => {
+                write!(f, "cannot read input file {}: {}", path.display(), err)?;
+                if *default {
+                    write!(f, " (save the puzzle input there, or pass --input <path> or - for stdin)")?;
+                }
+                Ok(())
+            }
+            Self::Stdin(err) => write!(f, "cannot read input from stdin: {}", err),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// Parses a day selection such as `5` or `5-8`.
+fn parse_day_range(arg: &str) -> Option<RangeInclusive<u32>> {
+    match arg.split_once('-') {
+        Some((first, last)) => Some(first.parse().ok()?..=last.parse().ok()?),
+        None => arg.parse().ok().map(|day| day..=day),
+    }
+}
+
+struct Args {
+    input: InputSource,
+    days: Vec<RangeInclusive<u32>>,
     operations: HashSet<String>,
+    record: bool,
+    /// day specific options, with an empty value for a bare flag
+    options: HashMap<String, String>,
+    log: LogConfig,
+}
+
+impl Args {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, InputError> {
+        let mut input = InputSource::Default;
+        let mut days = Vec::new();
+        let mut operations = HashSet::new();
+        let mut record = false;
+        let mut options = HashMap::new();
+        let mut verbosity = 0;
+        let mut log_specs = Vec::new();
+        while let Some(arg) = args.next() {
+            if arg == "--input" {
+                let path = args.next().ok_or(InputError::MissingValue(arg))?;
+                input = InputSource::from_arg(&path);
+            } else if let Some(path) = arg.strip_prefix("--input=") {
+                input = InputSource::from_arg(path);
+            } else if arg == "-" {
+                input = InputSource::Stdin;
+            } else if arg == "--record" {
+                record = true;
+            } else if let Some(spec) = arg.strip_prefix("--log=") {
+                log_specs.push(spec.to_string());
+            } else if let Some(flags @ ("v" | "vv" | "vvv" | "q" | "qq")) = arg.strip_prefix('-') {
+                verbosity += flags.matches('v').count() as i32 - flags.matches('q').count() as i32;
+            } else if let Some(option) = arg.strip_prefix("--") {
+                let (name, value) = option.split_once('=').unwrap_or((option, ""));
+                options.insert(name.to_string(), value.to_string());
+            } else if let Some(range) = parse_day_range(&arg) {
+                days.push(range);
+            } else {
+                operations.insert(arg);
+            }
+        }
+        let mut log = LogConfig { level: Level::from_verbosity(verbosity), ..LogConfig::default() };
+        for spec in log_specs {
+            log.add_spec(&spec).ok_or(InputError::InvalidLog(spec))?;
+        }
+        Ok(Self { input, days, operations, record, options, log })
+    }
+
+    fn day_enabled(&self, day: &Day) -> bool {
+        self.days.is_empty() || day.number().is_some_and(|n| self.days.iter().any(|r| r.contains(&n)))
+    }
+
+    /// Checks a custom input isn't shared by several days, which would also
+    /// read stdin once per day.
+    fn check_input(&self, selected_days: usize) -> Result<(), InputError> {
+        match self.input {
+            InputSource::Default => Ok(()),
+            _ if selected_days == 1 => Ok(()),
+            _ => Err(InputError::InputForDays(selected_days)),
+        }
+    }
+
+    /// Checks every operation and option named on the command line belongs
+    /// to one of the selected days, so that a typo doesn't quietly disable
+    /// everything or run with the defaults.
+    fn check_operations(&self, days: &[&Day]) -> Result<(), InputError> {
+        let operations = sorted_names(days.iter().flat_map(|d| d.operations.iter().map(|op| op.name)));
+        if let Some(name) = self.operations.iter().find(|name| !operations.contains(&name.as_str())) {
+            return Err(InputError::UnknownOperation(name.clone(), operations));
+        }
+        let options = sorted_names(days.iter().flat_map(|d| d.options.iter().copied()));
+        let mut given = self.options.keys().collect::<Vec<_>>();
+        given.sort();
+        match given.into_iter().find(|name| !options.contains(&name.as_str())) {
+            Some(name) => Err(InputError::UnknownOption(name.clone(), options)),
+            None => Ok(()),
+        }
+    }
+
+    fn operation_enabled(&self, op: &Named<Operation>) -> bool {
+        self.operations.is_empty() || self.operations.contains(op.name)
+    }
+}
+
+/// The distinct names, sorted for listing in an error message.
+fn sorted_names(names: impl Iterator<Item = &'static str>) -> Vec<&'static str> {
+    let mut names = names.collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
 }
 
-type Operation = fn(Lines) -> String;
+/// Path of the input checked in next to a day's `main.rs`, e.g. `src/bin/day01/input.txt`.
+fn default_input_path(day_name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/bin")
+        .join(day_name)
+        .join("input.txt")
+}
+
+fn load_input(day_name: &str, source: &InputSource) -> Result<String, InputError> {
+    match source {
+        InputSource::Default => {
+            let path = default_input_path(day_name);
+            fs::read_to_string(&path).map_err(|err| InputError::Read { path, default: true, err })
+        }
+        InputSource::File(path) => fs::read_to_string(path)
+            .map_err(|err| InputError::Read { path: path.clone(), default: false, err }),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map_err(InputError::Stdin)?;
+            Ok(input)
+        }
+    }
+}
+
+static OPTIONS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Looks up a day specific option given on the command line as `--name` or
+/// `--name=value`, where a bare flag has an empty value. The day must list
+/// the name in its `day!(...; options = [...])`, or the run is rejected.
+///
+/// Always `None` in tests, which don't go through the [Runner].
+pub fn option(name: &str) -> Option<&'static str> {
+    OPTIONS.get()?.get(name).map(String::as_str)
+}
+
+thread_local! {
+    static PARSE_TIME: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// Runs `parse` and attributes its duration to the parse column of the
+/// timing summary for the current operation, rather than to solving.
+pub fn timed_parse<T, F: FnOnce() -> T>(parse: F) -> T {
+    let start = Instant::now();
+    let parsed = parse();
+    let elapsed = start.elapsed();
+    PARSE_TIME.set(Some(PARSE_TIME.get().unwrap_or_default() + elapsed));
+    parsed
+}
+
+struct Report {
+    day: &'static str,
+    part: &'static str,
+    /// the error message if the operation failed
+    answer: Result<String, String>,
+    /// `None` unless running the day's own input, which answers are recorded for
+    check: Option<Check>,
+    parse: Option<Duration>,
+    total: Duration,
+}
+
+impl Report {
+    fn solve(&self) -> Duration {
+        self.total - self.parse.unwrap_or_default()
+    }
+}
+
+fn print_summary(reports: &[Report]) {
+    if reports.is_empty() {
+        return;
+    }
+    let header = ["day", "part", "answer", "check", "parse", "solve", "total"];
+    let rows = reports
+        .iter()
+        .map(|r| {
+            [
+                r.day.to_string(),
+                r.part.to_string(),
+                match &r.answer {
+                    Ok(answer) => answer.lines().next().unwrap_or_default().to_string(),
+                    Err(_) => String::from("(ERROR)"),
+                },
+                r.check.as_ref().map_or(String::from("-"), Check::to_string),
+                r.parse.map(|d| format!("{:.2?}", d)).unwrap_or_else(|| String::from("-")),
+                format!("{:.2?}", r.solve()),
+                format!("{:.2?}", r.total),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let widths = rows.iter().fold(header.map(str::len), |mut widths, row| {
+        // count chars rather than bytes since durations may contain µ
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+        widths
+    });
+    let print_row = |cells: &[&str]| {
+        // text columns are left aligned, durations right aligned
+        let line = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, w))| if i < 4 { format!("{:<w$}", cell) } else { format!("{:>w$}", cell) })
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+    println!();
+    print_row(&header);
+    print_row(&widths.map(|w| "-".repeat(w)).each_ref().map(String::as_str));
+    for row in rows.iter() {
+        print_row(&row.each_ref().map(String::as_str));
+    }
+    for r in reports {
+        match (&r.answer, &r.check) {
+            (Ok(answer), Some(Check::Fail { expected })) => {
+                println!("{} {}: expected {} but got {}", r.day, r.part, expected, answer)
+            }
+            (Err(err), _) => println!("{} {}: error: {}", r.day, r.part, err),
+            _ => {}
+        }
+    }
+}
+
+pub struct Runner {
+    args: Args,
+}
 
 impl Runner {
-    pub fn create(module_name: &'static str) -> Self {
-        Self {
-            module_name,
-            operations: std::env::args().skip(1).collect(),
+    /// Parses the command line, exiting with an error message if it's invalid.
+    ///
+    /// Arguments are day numbers or ranges like `5-8` and operation names to
+    /// run (default all), plus `--input <path>` to read a different input
+    /// file or `-` to read stdin, and `--record` to save the answers as the
+    /// expected answers for each day's own input. Diagnostics logged with
+    /// [log!] go to stderr at `info` level and above, raised by `-v` or `-vv`,
+    /// lowered by `-q`, or set per day or part like `--log=day08/part2=debug`.
+    /// Any other `--name` or `--name=value` is left for days to read with
+    /// [option], and must be one the selected days declare.
+    pub fn create() -> Self {
+        match Args::parse(std::env::args().skip(1)) {
+            Ok(args) => {
+                OPTIONS.get_or_init(|| args.options.clone());
+                LOG_CONFIG.get_or_init(|| args.log.clone());
+                Self { args }
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1)
+            }
         }
     }
 
-    pub fn run(&self, op: &Named<Operation>, input: &str) {
-        let enabled: bool = self.operations.is_empty() || self.operations.contains(op.name);
-        let result: String = if enabled {
-            (op.wrapped)(input.lines())
+    /// Runs the selected operations of the selected days, then prints a
+    /// timing summary of everything that ran.
+    ///
+    /// Fails if any input couldn't be read, any operation returned an error,
+    /// or any answer for a day's own input doesn't match its recorded answer.
+    pub fn run(&self, days: &[Day]) -> ExitCode {
+        let selected = days.iter().filter(|d| self.args.day_enabled(d)).collect::<Vec<_>>();
+        if let Err(err) = self.args.check_input(selected.len()).and_then(|_| self.args.check_operations(&selected)) {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+        graph::SEVERAL_DAYS.set(selected.len() > 1);
+        let mut reports = Vec::new();
+        let mut failed = false;
+        for day in selected {
+            if let Err(err) = self.run_day(day, &mut reports) {
+                eprintln!("{}: {}", day.name(), err);
+                failed = true;
+            }
+        }
+        print_summary(&reports);
+        if failed || reports.iter().any(|r| r.answer.is_err() || matches!(r.check, Some(Check::Fail { .. }))) {
+            ExitCode::FAILURE
         } else {
-            String::from("(DISABLED)")
+            ExitCode::SUCCESS
+        }
+    }
+
+    fn run_day(&self, day: &Day, reports: &mut Vec<Report>) -> Result<(), Box<dyn Error>> {
+        let input = load_input(day.name(), &self.args.input)?;
+        // answers are only known for the day's own input
+        let mut answers = match self.args.input {
+            InputSource::Default => Some(Answers::load(day.name())?),
+            _ if self.args.record => return Err("--record only applies to each day's own input".into()),
+            _ => None,
         };
-        println!("{} {}:\n{}", self.module_name, op.name, result);
+        for op in day.operations {
+            if let Some(mut report) = self.run_operation(day, op, &input) {
+                if let (Some(answers), Ok(answer)) = (answers.as_mut(), &report.answer) {
+                    report.check = Some(if self.args.record {
+                        answers.record(op.name, answer)
+                    } else {
+                        answers.check(op.name, answer)
+                    });
+                }
+                reports.push(report);
+            }
+        }
+        match answers {
+            Some(answers) if self.args.record => Ok(answers.save()?),
+            _ => Ok(()),
+        }
     }
-}
 
-#[macro_export]
-macro_rules! create_runner {
-    () => {
-        &Runner::create(module_path!())
-    };
+    fn run_operation(&self, day: &Day, op: &Named<Operation>, input: &str) -> Option<Report> {
+        let report = if self.args.operation_enabled(op) {
+            PARSE_TIME.set(None);
+            LOG_TARGET.set(Some((day.name(), op.name)));
+            let start = Instant::now();
+            let answer = (op.wrapped)(input.lines()).map_err(|err| err.to_string());
+            let total = start.elapsed();
+            LOG_TARGET.set(None);
+            Some(Report {
+                day: day.name(),
+                part: op.name,
+                answer,
+                check: None,
+                parse: PARSE_TIME.take(),
+                total,
+            })
+        } else {
+            None
+        };
+        let result = match report.as_ref().map(|r| &r.answer) {
+            Some(Ok(answer)) => answer.as_str(),
+            Some(Err(_)) => "(ERROR)",
+            None => "(DISABLED)",
+        };
+        println!("{} {}:\n{}", day.name(), op.name, result);
+        report
+    }
 }
 
 #[macro_export]
 macro_rules! verify {
+    ( $op:ident, $input:ident, Err($expected:expr) ) => {{
+        match $crate::IntoAnswer::into_answer($op($input.lines())) {
+            Ok(answer) => panic!(
+                "{} {}: expected error containing {:?} but got answer {:?}",
+                module_path!(),
+                std::stringify!($op),
+                $expected,
+                answer
+            ),
+            Err(err) => assert!(
+                err.to_string().contains($expected),
+                "{} {}: expected error containing {:?} but got {:?}",
+                module_path!(),
+                std::stringify!($op),
+                $expected,
+                err.to_string()
+            ),
+        }
+    }};
     ( $op:ident, $input:ident, $expected:expr ) => {{
-        let result = $op($input.lines());
+        let result = $crate::IntoAnswer::into_answer($op($input.lines())).map_err(|err| err.to_string());
         assert_eq!(
-            result,
-            $expected,
+            result.as_deref(),
+            Ok($expected),
             "{} {}",
             module_path!(),
             std::stringify!($op)
         );
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|a| a.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_args_input() {
+        assert_eq!(parse(&[]).input, InputSource::Default);
+        assert_eq!(parse(&["part2", "-"]).input, InputSource::Stdin);
+        assert_eq!(parse(&["--input", "-"]).input, InputSource::Stdin);
+        assert_eq!(parse(&["--input", "big.txt"]).input, InputSource::File(PathBuf::from("big.txt")));
+        assert_eq!(parse(&["--input=big.txt", "part1"]).operations, HashSet::from(["part1".to_string()]));
+        assert!(Args::parse(["--input".to_string()].into_iter()).is_err());
+        assert!(parse(&[]).check_input(10).is_ok());
+        assert!(parse(&["5", "-"]).check_input(1).is_ok());
+        assert!(parse(&["1-6", "-"]).check_input(6).is_err());
+        assert!(parse(&["--input", "big.txt"]).check_input(0).is_err());
+    }
+
+    #[test]
+    fn test_args_options() {
+        let args = parse(&["7", "--explain", "--bag=red=12", "part2"]);
+        assert_eq!(args.operations, HashSet::from(["part2".to_string()]));
+        assert_eq!(args.options.get("explain").map(String::as_str), Some(""));
+        assert_eq!(args.options.get("bag").map(String::as_str), Some("red=12"));
+        assert!(!parse(&["--record"]).options.contains_key("record"));
+        let args = parse(&["--explain", "part1"]);
+        assert_eq!(args.options.get("explain").map(String::as_str), Some(""));
+        assert_eq!(args.operations, HashSet::from(["part1".to_string()]));
+    }
+
+    #[test]
+    fn test_args_log() {
+        assert_eq!(parse(&[]).log.level, Level::Info);
+        assert_eq!(parse(&["-v", "-v"]).log.level, Level::Trace);
+        assert_eq!(parse(&["-q", "part1"]).log.level, Level::Warn);
+        let args = parse(&["-vv", "--log=warn,day08=debug"]);
+        assert_eq!(args.log.level, Level::Warn);
+        assert_eq!(args.log.targets, vec![("day08".to_string(), Level::Debug)]);
+        assert!(Args::parse(["--log=day08=loud".to_string()].into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_args_days() {
+        let args = parse(&["3", "5-8", "part2"]);
+        assert_eq!(args.days, vec![3..=3, 5..=8]);
+        assert_eq!(args.operations, HashSet::from(["part2".to_string()]));
+        let day = |module_path| Day::new(module_path, &[]);
+        assert!(args.day_enabled(&day("aoc::day06")));
+        assert!(!args.day_enabled(&day("day04")));
+        assert!(!args.day_enabled(&day("template")));
+        const OPS: &[Named<Operation>] = &[Named::create(|_| Ok(String::new()), "part2")];
+        assert!(args.check_operations(&[&Day::new("day03", OPS)]).is_ok());
+        let err = parse(&["english,german"]).check_operations(&[&Day::new("day01", OPS)]).unwrap_err();
+        assert!(err.to_string().starts_with("unknown operation \"english,german\", expected one of part2"));
+        let days = [&Day::new("day01", OPS).with_options(&["zero", "vocabulary"]), &Day::new("day03", OPS)];
+        assert!(parse(&["--vocabulary=german", "--zero"]).check_operations(&days).is_ok());
+        let err = parse(&["--vocabluary=german"]).check_operations(&days).unwrap_err();
+        assert_eq!(err.to_string(), "unknown option --vocabluary, expected one of --vocabulary, --zero");
+        let err = parse(&["--explain"]).check_operations(&days[1..]).unwrap_err();
+        assert_eq!(err.to_string(), "unknown option --explain, the selected days don't read any options");
+        assert!(parse(&[]).day_enabled(&day("template")));
+    }
+
+    #[test]
+    fn test_default_input_path() {
+        assert!(default_input_path("day01").ends_with("src/bin/day01/input.txt"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Diagnostics written to stderr, so that stdout only has answers, filtered
+//! by level for everything or for a particular day or part.
+
+use std::{cell::Cell, fmt, sync::OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|level| level.name() == name)
+    }
+
+    /// The default level `Info` raised by each `-v` and lowered by each `-q`.
+    pub(crate) fn from_verbosity(verbosity: i32) -> Self {
+        Self::ALL[(Level::Info as i32 + verbosity).clamp(0, Level::Trace as i32) as usize]
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogConfig {
+    pub level: Level,
+    /// levels for targets like `day08` or `day08/part2`, overriding `level`
+    pub targets: Vec<(String, Level)>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self { level: Level::Info, targets: Vec::new() }
+    }
+}
+
+impl LogConfig {
+    /// Parses `--log` specs such as `debug` or `day08=trace,day07/part2=warn`.
+    pub fn add_spec(&mut self, spec: &str) -> Option<()> {
+        for item in spec.split(',') {
+            match item.split_once('=') {
+                Some((target, level)) => self.targets.push((target.to_string(), Level::parse(level)?)),
+                None => self.level = Level::parse(item)?,
+            }
+        }
+        Some(())
+    }
+
+    /// The level for the most specific target matching `day` and `part`.
+    fn level_for(&self, day: &str, part: &str) -> Level {
+        self.targets
+            .iter()
+            .filter(|(target, _)| match target.split_once('/') {
+                Some((d, p)) => d == day && p == part,
+                None => target == day,
+            })
+            .max_by_key(|(target, _)| target.contains('/'))
+            .map_or(self.level, |&(_, level)| level)
+    }
+}
+
+pub(crate) static LOG_CONFIG: OnceLock<LogConfig> = OnceLock::new();
+
+thread_local! {
+    /// day and part of the running operation, which log messages are attributed to
+    pub(crate) static LOG_TARGET: Cell<Option<(&'static str, &'static str)>> = const { Cell::new(None) };
+}
+
+/// Whether a message at `level` from the running operation would be written.
+pub fn log_enabled(level: Level) -> bool {
+    let default = LogConfig::default();
+    let config = LOG_CONFIG.get().unwrap_or(&default);
+    let (day, part) = LOG_TARGET.get().unwrap_or_default();
+    level <= config.level_for(day, part)
+}
+
+/// Writes a message to stderr, prefixed with its level and the running
+/// operation. Use the [log!](crate::log!) macro rather than calling this.
+pub fn log_message(level: Level, args: fmt::Arguments) {
+    match LOG_TARGET.get() {
+        Some((day, part)) => eprintln!("{} {} {}: {}", level, day, part, args),
+        None => eprintln!("{}: {}", level, args),
+    }
+}
+
+/// Logs a message to stderr if its level is enabled, e.g.
+/// `log!(Debug, "cycle length {}", len)`.
+#[macro_export]
+macro_rules! log {
+    ( $level:ident, $($arg:tt)* ) => {
+        if $crate::log_enabled($crate::Level::$level) {
+            $crate::log_message($crate::Level::$level, format_args!($($arg)*))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(Level::from_verbosity(0), Level::Info);
+        assert_eq!(Level::from_verbosity(2), Level::Trace);
+        assert_eq!(Level::from_verbosity(5), Level::Trace);
+        assert_eq!(Level::from_verbosity(-1), Level::Warn);
+        assert_eq!(Level::from_verbosity(-3), Level::Error);
+
+        let mut config = LogConfig::default();
+        assert_eq!(config.add_spec("warn,day08=debug,day08/part2=trace"), Some(()));
+        assert_eq!(config.level_for("day07", "part2"), Level::Warn);
+        assert_eq!(config.level_for("day08", "part1"), Level::Debug);
+        assert_eq!(config.level_for("day08", "part2"), Level::Trace);
+        assert_eq!(config.add_spec("day08=loud"), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! A parse error shared by the days, pointing at where in a line of the input
+//! parsing failed.
+
+use std::{error::Error, fmt, ops::Range};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number within the input, if known
+    pub line: Option<usize>,
+    /// the line (or part of one) that failed to parse
+    pub text: String,
+    /// byte range of the offending part of `text`
+    pub span: Range<usize>,
+    /// description of what should have been there, e.g. `card value`
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(text: &str, span: Range<usize>, expected: impl Into<String>) -> Self {
+        Self { line: None, text: text.to_string(), span, expected: expected.into() }
+    }
+
+    /// An error covering all of `text`.
+    pub fn expected(text: &str, expected: impl Into<String>) -> Self {
+        Self::new(text, 0..text.len(), expected)
+    }
+
+    /// An error covering `part`, which must be a slice of `text` such as a
+    /// regex capture or the result of splitting `text`.
+    pub fn at(text: &str, part: &str, expected: impl Into<String>) -> Self {
+        let start = (part.as_ptr() as usize)
+            .checked_sub(text.as_ptr() as usize)
+            .filter(|start| start + part.len() <= text.len())
+            .expect("part must be a slice of text");
+        Self::new(text, start..start + part.len(), expected)
+    }
+
+    /// Moves an error about `part` to be about all of `text`, which `part`
+    /// must be a slice of, so that it points at the same place in `text`.
+    pub fn within(self, text: &str, part: &str) -> Self {
+        let outer = Self::at(text, part, self.expected);
+        let start = outer.span.start;
+        Self { span: start + self.span.start..start + self.span.end, ..outer }
+    }
+
+    pub fn at_line(self, line: usize) -> Self {
+        Self { line: Some(line), ..self }
+    }
+
+    /// 1-based column of the start of the span, counting chars rather than bytes.
+    pub fn column(&self) -> usize {
+        self.text[..self.span.start].chars().count() + 1
+    }
+
+    /// Renders the error with the offending text underlined, e.g.
+    /// ```text
+    /// line 2, column 6: expected game id
+    ///   |
+    /// 2 | Game two: 1 red
+    ///   |      ^^^
+    /// ```
+    pub fn render(&self) -> String {
+        let line_label = self.line.map(|n| n.to_string()).unwrap_or_default();
+        let gutter = " ".repeat(line_label.len());
+        let location = match self.line {
+            Some(line) => format!("line {}, column {}", line, self.column()),
+            None => format!("column {}", self.column()),
+        };
+        let carets = self.text[self.span.clone()].chars().count().max(1);
+        format!(
+            "{}: expected {}\n{} |\n{} | {}\n{} | {}{}",
+            location,
+            self.expected,
+            gutter,
+            line_label,
+            self.text,
+            gutter,
+            " ".repeat(self.column() - 1),
+            "^".repeat(carets),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Several parse errors found in one go, so that they can all be fixed at
+/// once rather than one run at a time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl From<ParseError> for ParseErrors {
+    fn from(error: ParseError) -> Self {
+        Self(vec![error])
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rendered = self.0.iter().map(ParseError::render).collect::<Vec<_>>();
+        f.write_str(&rendered.join("\n"))
+    }
+}
+
+impl Error for ParseErrors {}
+
+/// Parses each item of an iterator of lines, attaching line numbers to errors.
+pub trait ParseLines<'a>: Iterator<Item = &'a str> + Sized {
+    /// Like [ParseLines::parse_lines] for lines which don't start at the
+    /// beginning of the input, such as after taking a header line.
+    fn parse_lines_from<T, F>(self, first_line: usize, mut parse: F) -> impl Iterator<Item = Result<T, ParseError>>
+    where
+        F: FnMut(&'a str) -> Result<T, ParseError>,
+    {
+        self.zip(first_line..).map(move |(line, n)| parse(line).map_err(|e| e.at_line(n)))
+    }
+
+    fn parse_lines<T, F>(self, parse: F) -> impl Iterator<Item = Result<T, ParseError>>
+    where
+        F: FnMut(&'a str) -> Result<T, ParseError>,
+    {
+        self.parse_lines_from(1, parse)
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> ParseLines<'a> for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let line = "Game two: 1 red";
+        let error = ParseError::at(line, &line[5..8], "game id").at_line(12);
+        assert_eq!(error.span, 5..8);
+        assert_eq!(
+            error.render(),
+            "line 12, column 6: expected game id\n   |\n12 | Game two: 1 red\n   |      ^^^"
+        );
+        assert_eq!(
+            ParseError::new("ab", 2..2, "digit").render(),
+            "column 3: expected digit\n |\n | ab\n |   ^"
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let parse = |line: &str| line.parse::<u32>().map_err(|_| ParseError::expected(line, "number"));
+        let results = "1\n2\nx".lines().parse_lines(parse).collect::<Vec<_>>();
+        assert_eq!(results[..2], [Ok(1), Ok(2)]);
+        assert_eq!(results[2].as_ref().unwrap_err().line, Some(3));
+        let error = "x".lines().parse_lines_from(5, parse).next().unwrap().unwrap_err();
+        assert_eq!(error.line, Some(5));
+    }
+}
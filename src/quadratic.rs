@@ -0,0 +1,168 @@
+//! Exact integer answers about quadratics, like how many integers make one
+//! positive, without the rounding trouble of floating point square roots.
+
+use num::{
+    integer::{Average, Roots},
+    Integer, Signed,
+};
+use std::ops::Range;
+
+/// Finds the first integer in `range` where `pred` is false, or `range.end`
+/// if there isn't one, given that `pred` is true for some prefix of the range
+/// and false after it, like [slice::partition_point].
+pub fn partition_point<T, P>(range: Range<T>, mut pred: P) -> T
+where
+    T: Integer + Average + Clone,
+    P: FnMut(&T) -> bool,
+{
+    let Range { start: mut lo, end: mut hi } = range;
+    while lo < hi {
+        // rounds down without overflowing, so lo <= mid < hi
+        let mid = lo.average_floor(&hi);
+        if pred(&mid) {
+            lo = mid + T::one();
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// `a·x² + b·x + c`, which may also be linear or constant.
+///
+/// Coefficients must be a signed type like `i64` or `BigInt`, since working
+/// with the vertex and roots needs negative values even for a quadratic like
+/// `-x² + 7x - 9` whose interesting values are positive. For unsigned values,
+/// search a monotone part of the quadratic with [partition_point] instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quadratic<T> {
+    pub a: T,
+    pub b: T,
+    pub c: T,
+}
+
+impl<T: Integer + Signed + Average + Clone> Quadratic<T> {
+    pub fn new(a: T, b: T, c: T) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn eval(&self, x: &T) -> T {
+        (self.a.clone() * x.clone() + self.b.clone()) * x.clone() + self.c.clone()
+    }
+
+    /// Counts the integers `x` in `lo..=hi` where the value is positive.
+    ///
+    /// The value only changes direction at the vertex, so the range is split
+    /// there and each side's boundary found with [partition_point].
+    pub fn count_positive(&self, lo: T, hi: T) -> T {
+        let two = T::one() + T::one();
+        // the last integer on the side of the vertex where the value falls if `a` is positive
+        let split = if !self.a.is_zero() {
+            (-self.b.clone()).div_floor(&(two * self.a.clone()))
+        } else if self.b < T::zero() {
+            hi.clone()
+        } else {
+            lo.clone() - T::one()
+        };
+        // with a positive `a` the value falls until the vertex, and a line is all
+        // on one side of the split
+        let falls_first = self.a >= T::zero();
+        let first_end = split.clone().min(hi.clone()) + T::one();
+        let second_start = lo.clone().max(split + T::one());
+        self.count_monotone(lo..first_end, falls_first) + self.count_monotone(second_start..hi + T::one(), !falls_first)
+    }
+
+    /// Counts positive values in `range`, over which the value only falls or
+    /// only rises.
+    fn count_monotone(&self, range: Range<T>, falling: bool) -> T {
+        if range.start >= range.end {
+            return T::zero();
+        }
+        let Range { start, end } = range.clone();
+        if falling {
+            partition_point(range, |x| self.eval(x) > T::zero()) - start
+        } else {
+            end - partition_point(range, |x| self.eval(x) <= T::zero())
+        }
+    }
+}
+
+impl<T: Integer + Signed + Average + Roots + Clone> Quadratic<T> {
+    /// The integers where the value is zero, in increasing order, or `None`
+    /// for the zero polynomial where every integer is.
+    pub fn integer_roots(&self) -> Option<Vec<T>> {
+        let Self { a, b, c } = self.clone();
+        let mut roots = Vec::new();
+        if a.is_zero() {
+            if b.is_zero() {
+                return (!c.is_zero()).then_some(roots);
+            }
+            let (root, rem) = (-c).div_rem(&b);
+            if rem.is_zero() {
+                roots.push(root);
+            }
+            return Some(roots);
+        }
+        let two = T::one() + T::one();
+        let discriminant = b.clone() * b.clone() - two.clone() * two.clone() * a.clone() * c;
+        if discriminant < T::zero() {
+            return Some(roots);
+        }
+        let sqrt = discriminant.sqrt();
+        if sqrt.clone() * sqrt.clone() != discriminant {
+            return Some(roots);
+        }
+        for numerator in [-b.clone() - sqrt.clone(), -b + sqrt] {
+            let (root, rem) = numerator.div_rem(&(two.clone() * a.clone()));
+            if rem.is_zero() {
+                roots.push(root);
+            }
+        }
+        roots.sort();
+        roots.dedup();
+        Some(roots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigInt;
+
+    #[test]
+    fn test_partition_point() {
+        assert_eq!(partition_point(0..100, |&x| x * x < 50), 8);
+        assert_eq!(partition_point(-10..10, |&x| x < -3), -3);
+        assert_eq!(partition_point(5..5, |_| true), 5);
+        assert_eq!(partition_point(i64::MIN..i64::MAX, |&x| x < i64::MAX - 1), i64::MAX - 1);
+        assert_eq!(partition_point(0u8..255, |_| true), 255);
+    }
+
+    #[test]
+    fn test_integer_roots() {
+        assert_eq!(Quadratic::new(1, -3, 2).integer_roots(), Some(vec![1, 2]));
+        assert_eq!(Quadratic::new(-2, 0, 8).integer_roots(), Some(vec![-2, 2]));
+        assert_eq!(Quadratic::new(1, -4, 4).integer_roots(), Some(vec![2]));
+        assert_eq!(Quadratic::new(2, -1, 0).integer_roots(), Some(vec![0]));
+        assert_eq!(Quadratic::new(1, 0, 1).integer_roots(), Some(vec![]));
+        assert_eq!(Quadratic::new(0, 3, -6).integer_roots(), Some(vec![2]));
+        assert_eq!(Quadratic::new(0, 0, 0).integer_roots(), None);
+    }
+
+    #[test]
+    fn test_count_positive_brute_force() {
+        for a in -3i64..=3 {
+            for b in -6..=6 {
+                for c in -8..=8 {
+                    let q = Quadratic::new(a, b, c);
+                    for (lo, hi) in [(-10, 10), (0, 4), (3, -3), (-7, -2)] {
+                        let expected = (lo..=hi).filter(|x| q.eval(x) > 0).count() as i64;
+                        assert_eq!(q.count_positive(lo, hi), expected, "{:?} over {}..={}", q, lo, hi);
+                    }
+                }
+            }
+        }
+        let big = Quadratic::new(BigInt::from(-1), BigInt::from(30), BigInt::from(-201));
+        assert_eq!(big.count_positive(BigInt::from(0), BigInt::from(30)), BigInt::from(9));
+    }
+}
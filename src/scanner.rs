@@ -0,0 +1,139 @@
+//! Finds every occurrence of a set of tokens in one pass over some text,
+//! including occurrences which overlap, like `one` and `eight` in `oneight`.
+//! It's an Aho–Corasick automaton: a trie of the tokens where each state also
+//! links to the longest proper suffix of it which is in the trie, so scanning
+//! never backtracks.
+
+use std::collections::{BTreeMap, VecDeque};
+
+/// An occurrence of a token at byte range `start..end` of the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<'a, V> {
+    pub start: usize,
+    pub end: usize,
+    /// the value given for the token
+    pub value: &'a V,
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    next: BTreeMap<u8, usize>,
+    /// state for the longest proper suffix of this state's text in the trie
+    fail: usize,
+    /// tokens ending here, longest first, including those of suffixes
+    tokens: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scanner<V> {
+    states: Vec<State>,
+    /// length and value of each token
+    tokens: Vec<(usize, V)>,
+}
+
+impl<V> Scanner<V> {
+    /// A scanner for `tokens` paired with the value to report for each.
+    /// Empty tokens are ignored.
+    pub fn new<S: AsRef<str>>(tokens: impl IntoIterator<Item = (S, V)>) -> Self {
+        let mut states = vec![State::default()];
+        let tokens = tokens
+            .into_iter()
+            .filter(|(token, _)| !token.as_ref().is_empty())
+            .enumerate()
+            .map(|(index, (token, value))| {
+                let token = token.as_ref();
+                let mut state = 0;
+                for &byte in token.as_bytes() {
+                    state = match states[state].next.get(&byte) {
+                        Some(&next) => next,
+                        None => {
+                            states.push(State::default());
+                            let next = states.len() - 1;
+                            states[state].next.insert(byte, next);
+                            next
+                        }
+                    };
+                }
+                states[state].tokens.push(index);
+                (token.len(), value)
+            })
+            .collect::<Vec<_>>();
+
+        // breadth first, so the fail state of a parent is done before its children
+        let mut queue = states[0].next.values().copied().collect::<VecDeque<_>>();
+        while let Some(state) = queue.pop_front() {
+            let children = states[state].next.clone();
+            for (byte, child) in children {
+                let mut fail = states[state].fail;
+                let child_fail = loop {
+                    if let Some(&next) = states[fail].next.get(&byte) {
+                        break next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = states[fail].fail;
+                };
+                states[child].fail = child_fail;
+                let inherited = states[child_fail].tokens.clone();
+                states[child].tokens.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+        Self { states, tokens }
+    }
+
+    /// Every match in `text`, ordered by where they end, and longest first
+    /// for matches ending at the same place.
+    pub fn find_overlapping<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match<'a, V>> + 'a {
+        text.bytes()
+            .enumerate()
+            .scan(0, move |state, (i, byte)| {
+                *state = self.step(*state, byte);
+                Some((i + 1, *state))
+            })
+            .flat_map(move |(end, state)| {
+                self.states[state].tokens.iter().map(move |&token| {
+                    let (len, value) = &self.tokens[token];
+                    Match { start: end - len, end, value }
+                })
+            })
+    }
+
+    fn step(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.states[state].next.get(&byte) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.states[state].fail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(scanner: &Scanner<u32>, text: &str) -> Vec<(usize, usize, u32)> {
+        scanner.find_overlapping(text).map(|m| (m.start, m.end, *m.value)).collect()
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let scanner = Scanner::new([("one", 1), ("eight", 8), ("two", 2), ("1", 1)]);
+        assert_eq!(matches(&scanner, "oneight"), vec![(0, 3, 1), (2, 7, 8)]);
+        assert_eq!(matches(&scanner, "xtwone1"), vec![(1, 4, 2), (3, 6, 1), (6, 7, 1)]);
+        assert_eq!(matches(&scanner, "on e"), vec![]);
+    }
+
+    #[test]
+    fn test_nested() {
+        // the classic example, where tokens are suffixes and prefixes of each other
+        let scanner = Scanner::new([("he", 0), ("she", 1), ("his", 2), ("hers", 3), ("", 4)]);
+        assert_eq!(matches(&scanner, "ushers"), vec![(1, 4, 1), (2, 4, 0), (2, 6, 3)]);
+        assert_eq!(matches(&scanner, "ahishe"), vec![(1, 4, 2), (3, 6, 1), (4, 6, 0)]);
+    }
+}
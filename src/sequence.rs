@@ -0,0 +1,103 @@
+//! Sequences given by a polynomial in their index, found from a run of
+//! values by repeatedly taking differences, then evaluated anywhere using big
+//! integers so that extrapolating far can't overflow.
+
+use num::{BigInt, One, Zero};
+
+/// A polynomial stored as its Newton forward differences at index 0, so
+/// `f(n) = Σ Δᵏf(0) · C(n, k)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// `Δᵏf(0)` for each `k` up to the degree
+    differences: Vec<BigInt>,
+    /// how many values the polynomial was fitted to
+    len: usize,
+}
+
+/// `C(n, k)` for each `k` in turn, which is also defined for negative `n`.
+fn binomials(n: &BigInt) -> impl Iterator<Item = BigInt> + '_ {
+    (0u32..).scan(BigInt::one(), move |c, k| {
+        let value = c.clone();
+        // C(n, k+1) = C(n, k) · (n-k) / (k+1), where the division is exact
+        *c = &*c * (n - k) / (k + 1);
+        Some(value)
+    })
+}
+
+impl Polynomial {
+    /// Fits the lowest degree polynomial through `values` at indexes `0..`.
+    pub fn fit<T: Into<BigInt>>(values: impl IntoIterator<Item = T>) -> Self {
+        let mut row = values.into_iter().map(Into::into).collect::<Vec<BigInt>>();
+        let len = row.len();
+        let mut differences = Vec::new();
+        while !row.is_empty() && !row.iter().all(Zero::is_zero) {
+            differences.push(row[0].clone());
+            row = row.windows(2).map(|w| &w[1] - &w[0]).collect();
+        }
+        Self { differences, len }
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.differences.len().checked_sub(1)
+    }
+
+    /// Whether there were more values than needed to fit the polynomial, so
+    /// that its degree was confirmed by a row of zero differences rather
+    /// than just running out of values.
+    pub fn confirmed(&self) -> bool {
+        self.differences.len() < self.len
+    }
+
+    /// The value at `index`, which may be negative or past the fitted values.
+    pub fn at(&self, index: impl Into<BigInt>) -> BigInt {
+        let index = index.into();
+        self.differences.iter().zip(binomials(&index)).map(|(d, c)| d * c).sum()
+    }
+
+    /// The value `steps` after the last fitted value.
+    pub fn after(&self, steps: usize) -> BigInt {
+        self.at(self.len as i64 - 1 + steps as i64)
+    }
+
+    /// The value `steps` before the first fitted value.
+    pub fn before(&self, steps: usize) -> BigInt {
+        self.at(-(steps as i64))
+    }
+
+    /// The value at `index` if it fits in an `i64`.
+    pub fn checked_at(&self, index: i64) -> Option<i64> {
+        i64::try_from(self.at(index)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit() {
+        let p = Polynomial::fit([1, 3, 6, 10, 15, 21]);
+        assert_eq!(p.degree(), Some(2));
+        assert!(p.confirmed());
+        assert_eq!(p.after(1), 28.into());
+        assert_eq!(p.before(1), 0.into());
+        assert_eq!(p.before(3), 1.into());
+        assert_eq!(p.at(100), (101 * 102 / 2).into());
+
+        assert_eq!(Polynomial::fit([0, 0, 0]).degree(), None);
+        assert_eq!(Polynomial::fit([7, 7]).degree(), Some(0));
+        assert!(!Polynomial::fit([1, 2, 4]).confirmed());
+        assert_eq!(Polynomial::fit(Vec::<i64>::new()).at(5), 0.into());
+    }
+
+    #[test]
+    fn test_big() {
+        // n⁵, far enough along that it no longer fits in an i64
+        let p = Polynomial::fit((0..8).map(|n: i64| n.pow(5)));
+        assert_eq!(p.degree(), Some(5));
+        assert_eq!(p.checked_at(-6000), Some(-6000i64.pow(5)));
+        assert_eq!(p.checked_at(7000), None);
+        assert_eq!(p.after(7000 - 7), BigInt::from(7000).pow(5));
+    }
+}